@@ -0,0 +1,101 @@
+//! Loading people and jobs from files, the format is picked by the file extension
+//!
+//! JSON files are either a bare list or an object with a `people` or `jobs` list, which also
+//!  reads the web app's stored data. TOML files have a `people` or `jobs` array of tables. CSV
+//!  files have a header row, `name,ability,tags` for people and `name,needs,when` for jobs.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use kp_chart::data::{parse_tags, Ability, Job, Person, Recurrence, Slot};
+
+#[derive(Deserialize)]
+struct PersonRow {
+    name: String,
+    ability: String,
+    /// comma separated skill tags
+    #[serde(default)]
+    tags: String,
+}
+
+#[derive(Deserialize)]
+struct JobRow {
+    name: String,
+    /// semicolon separated slots, e.g. "Adult +driver; Teen"
+    needs: String,
+    /// when the job is done, see `Recurrence`'s `FromStr`, every day if empty
+    #[serde(default)]
+    when: String,
+}
+
+pub fn read_people(path: &Path) -> Result<Vec<Person>, Box<dyn Error>> {
+    read(path, "people", |row: PersonRow| {
+        let mut person = Person::new(row.name, Ability::new(row.ability.trim()));
+        person.set_tags(parse_tags(&row.tags));
+        Ok(person)
+    })
+}
+
+pub fn read_jobs(path: &Path) -> Result<Vec<Job>, Box<dyn Error>> {
+    read(path, "jobs", |row: JobRow| {
+        let slots = row
+            .needs
+            .split(';')
+            .filter(|s| !s.trim().is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<Slot>, _>>()?;
+        let recurrence = row.when.parse::<Recurrence>()?;
+        Ok(Job::new(row.name, slots).with_recurrence(recurrence))
+    })
+}
+
+fn read<T, R, F>(path: &Path, key: &str, from_row: F) -> Result<Vec<T>, Box<dyn Error>>
+where
+    T: DeserializeOwned,
+    R: DeserializeOwned,
+    F: Fn(R) -> Result<T, Box<dyn Error>>,
+{
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    match extension.as_str() {
+        "json" => {
+            let value: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+            let list = if value.is_array() {
+                value
+            } else {
+                value
+                    .get(key)
+                    .cloned()
+                    .ok_or_else(|| format!("no {} in {}", key, path.display()))?
+            };
+            Ok(serde_json::from_value(list)?)
+        }
+        "toml" => {
+            let table: toml::Table = toml::from_str(&fs::read_to_string(path)?)?;
+            let list = table
+                .get(key)
+                .cloned()
+                .ok_or_else(|| format!("no {} in {}", key, path.display()))?;
+            Ok(list.try_into()?)
+        }
+        "csv" => csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_path(path)?
+            .deserialize()
+            .map(|row| from_row(row?))
+            .collect(),
+        _ => Err(format!(
+            "unknown file type, expected .json, .toml or .csv: {}",
+            path.display()
+        )
+        .into()),
+    }
+}
@@ -0,0 +1,125 @@
+//! Command line Kitchen Patrol chart generator
+//!
+//! Reads the people and jobs from JSON, TOML or CSV files, calculates the chart and prints it as
+//!  a text table, Markdown or JSON.
+
+mod input;
+mod output;
+
+use std::error::Error;
+use std::path::PathBuf;
+use std::process;
+
+use chrono::{Local, NaiveDate};
+use clap::{Parser, ValueEnum};
+use kp_chart::data::{validate_jobs, validate_people, Abilities, Ability, Job, Settings};
+
+#[derive(Parser)]
+#[command(name = "kp-chart", version, about = "Generates a Kitchen Patrol chart")]
+struct Args {
+    /// People to schedule, a .json, .toml or .csv file, defaults to the example family
+    #[arg(long)]
+    people: Option<PathBuf>,
+
+    /// Jobs to schedule, a .json, .toml or .csv file, defaults to the example jobs
+    #[arg(long)]
+    jobs: Option<PathBuf>,
+
+    /// Built in job template to use instead of a jobs file, e.g. "ski cabin"
+    #[arg(long, conflicts_with = "jobs")]
+    template: Option<String>,
+
+    /// Number of days in the chart
+    #[arg(long, default_value_t = Settings::default().num_days())]
+    days: usize,
+
+    /// First day of the chart, e.g. 2020-07-01, defaults to today
+    #[arg(long)]
+    start: Option<NaiveDate>,
+
+    /// Ability groups in order, comma separated, the first group's rotation is offset daily
+    #[arg(long, value_delimiter = ',')]
+    abilities: Vec<String>,
+
+    /// How to print the chart
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Text,
+    Markdown,
+    Json,
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let people = match args.people {
+        Some(ref path) => input::read_people(path)?,
+        None => kp_chart::default_people(),
+    };
+    let jobs = match (args.jobs, args.template) {
+        (Some(ref path), _) => input::read_jobs(path)?,
+        (None, Some(ref name)) => template_jobs(name)?,
+        (None, None) => kp_chart::default_jobs(),
+    };
+    let abilities = if args.abilities.is_empty() {
+        Abilities::default()
+    } else {
+        Abilities::new(
+            args.abilities
+                .iter()
+                .map(|a| Ability::new(a.trim()))
+                .collect(),
+        )
+    };
+
+    for (person, errors) in people.iter().zip(validate_people(&people, &abilities)) {
+        if let Some(err) = errors.name.or(errors.ability) {
+            return Err(format!("person \"{}\": {}", person.name(), err).into());
+        }
+    }
+    for (job, errors) in jobs.iter().zip(validate_jobs(&jobs)) {
+        if let Some(err) = errors.name.or(errors.slots).or(errors.recurrence) {
+            return Err(format!("job \"{}\": {}", job.name(), err).into());
+        }
+    }
+
+    let start = args.start.unwrap_or_else(|| Local::now().date_naive());
+    let settings = Settings::new(start, args.days);
+    let week = kp_chart::calculate(&settings, &abilities, jobs, people);
+
+    match args.format {
+        Format::Text => print!("{}", week),
+        Format::Markdown => print!("{}", output::markdown(&week)),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&week)?),
+    }
+
+    Ok(())
+}
+
+/// The jobs of the built in template with the name, ignoring case
+fn template_jobs(name: &str) -> Result<Vec<Job>, Box<dyn Error>> {
+    let templates = kp_chart::builtin_templates();
+    match templates
+        .iter()
+        .find(|t| t.name().eq_ignore_ascii_case(name.trim()))
+    {
+        Some(template) => Ok(template.jobs().to_vec()),
+        None => {
+            let names = templates
+                .iter()
+                .map(|t| t.name())
+                .collect::<Vec<_>>()
+                .join(", ");
+            Err(format!("no template \"{}\", try one of: {}", name, names).into())
+        }
+    }
+}
+
+fn main() {
+    if let Err(err) = run(Args::parse()) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,23 @@
+use kp_chart::data::Week;
+
+/// The week as a Markdown table, one row per job and a column for each day
+pub fn markdown(week: &Week) -> String {
+    let rows = week.to_rows();
+    let line = |cells: &[String]| {
+        let cells = cells
+            .iter()
+            .map(|c| c.replace('|', "\\|"))
+            .collect::<Vec<_>>();
+        format!("| {} |\n", cells.join(" | "))
+    };
+
+    let mut table = String::new();
+    if let Some((header, jobs)) = rows.split_first() {
+        table.push_str(&line(header));
+        table.push_str(&line(&vec!["---".to_string(); header.len()]));
+        for job in jobs {
+            table.push_str(&line(job));
+        }
+    }
+    table
+}
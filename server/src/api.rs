@@ -0,0 +1,267 @@
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+
+use kp_chart::data::{ChangeLog, PeopleOp};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::store::*;
+
+type Store = Arc<FileStore>;
+
+impl IntoResponse for StoreError {
+    fn into_response(self) -> Response {
+        eprintln!("error: {}", self);
+        (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+    }
+}
+
+async fn get_collection<C: Collection>(State(store): State<Store>) -> Result<Response, StoreError> {
+    Ok(match store.get::<C>()? {
+        Some(document) => Json(document).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    })
+}
+
+/// The version a document was changed from, 0 if it's new to the server
+#[derive(Default, Deserialize)]
+struct Base {
+    #[serde(default)]
+    base: usize,
+}
+
+/// Stores a document changed from version `base`, giving it the next version
+///
+/// If anyone else saved since then it's refused as a conflict, along with the current document.
+async fn put_collection<C: Collection>(
+    State(store): State<Store>,
+    Query(Base { base }): Query<Base>,
+    Json(mut document): Json<C>,
+) -> Result<Response, StoreError> {
+    store.update(|current: Option<C>| {
+        let inc = current.as_ref().map_or(0, C::inc);
+        if inc != base {
+            let response = match current {
+                Some(current) => (StatusCode::CONFLICT, Json(current)).into_response(),
+                None => StatusCode::CONFLICT.into_response(),
+            };
+            return (None, response);
+        }
+
+        document.set_inc(inc + 1);
+        let response = Json(json!({ "inc": document.inc() })).into_response();
+        (Some(document), response)
+    })
+}
+
+/// Applies edits made on top of version `base` of the people
+///
+/// If anyone else saved since then the edits are refused as a conflict, along with the current people.
+async fn post_people_ops(
+    State(store): State<Store>,
+    Json(log): Json<ChangeLog<PeopleOp>>,
+) -> Result<Response, StoreError> {
+    store.update(|people: Option<People>| {
+        let mut people = people.unwrap_or(People {
+            inc: 0,
+            people: Vec::new(),
+        });
+        if people.inc != log.base {
+            return (None, (StatusCode::CONFLICT, Json(people)).into_response());
+        }
+
+        for op in log.ops.iter() {
+            if !op.apply(&mut people.people) {
+                let error = format!("no such person for {:?}", op);
+                return (
+                    None,
+                    (StatusCode::UNPROCESSABLE_ENTITY, error).into_response(),
+                );
+            }
+        }
+
+        people.inc += 1;
+        let response = Json(&people).into_response();
+        (Some(people), response)
+    })
+}
+
+fn route<C: Collection>(router: Router<Store>) -> Router<Store> {
+    router.route(
+        &format!("/{}", C::NAME),
+        get(get_collection::<C>).put(put_collection::<C>),
+    )
+}
+
+/// `GET` and `PUT` for each collection, e.g. `/people`, in the same JSON the web app stores locally,
+///  and `POST /people/ops` for edits to the people
+///
+/// A `PUT` names the version it was changed from, e.g. `/jobs?base=3`.
+pub fn router(store: FileStore) -> Router {
+    let router = Router::new();
+    let router = route::<People>(router).route("/people/ops", post(post_people_ops));
+    let router = route::<Jobs>(router);
+    let router = route::<AbilityGroups>(router);
+    let router = route::<ChartSettings>(router);
+    let router = route::<Schedule>(router);
+
+    router.with_state(Arc::new(store))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::process;
+
+    use axum::body;
+    use kp_chart::data::{Ability, Job, Person};
+    use serde::de::DeserializeOwned;
+
+    use super::*;
+
+    /// A store in its own temporary directory, removed when it's dropped
+    struct TestStore(Store);
+
+    impl TestStore {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("kp-chart-{}-{}", name, process::id()));
+            fs::remove_dir_all(&dir).ok();
+            Self(Arc::new(FileStore::open(dir).unwrap()))
+        }
+
+        fn state(&self) -> State<Store> {
+            State(self.0.clone())
+        }
+    }
+
+    impl Drop for TestStore {
+        fn drop(&mut self) {
+            fs::remove_dir_all(self.0.dir()).ok();
+        }
+    }
+
+    async fn read<T: DeserializeOwned>(response: Response) -> T {
+        let body = body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn jobs(names: &[&str]) -> Jobs {
+        Jobs {
+            inc: 0,
+            jobs: names
+                .iter()
+                .map(|n| Job::new(*n, vec![Ability::new("Adult")]))
+                .collect(),
+        }
+    }
+
+    async fn put_jobs(store: &TestStore, base: usize, names: &[&str]) -> Response {
+        put_collection::<Jobs>(store.state(), Query(Base { base }), Json(jobs(names)))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn put_gives_each_save_the_next_version() {
+        let store = TestStore::new("put-versions");
+
+        let response = put_jobs(&store, 0, &["Dinner"]).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            read::<serde_json::Value>(response).await,
+            json!({ "inc": 1 })
+        );
+
+        let response = put_jobs(&store, 1, &["Dinner", "Dishes"]).await;
+        assert_eq!(
+            read::<serde_json::Value>(response).await,
+            json!({ "inc": 2 })
+        );
+        assert_eq!(store.0.get::<Jobs>().unwrap().unwrap().jobs.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn put_from_an_old_version_is_a_conflict() {
+        let store = TestStore::new("put-conflict");
+        put_jobs(&store, 0, &["Dinner"]).await;
+        put_jobs(&store, 1, &["Dinner", "Dishes"]).await;
+
+        let response = put_jobs(&store, 1, &["Lunch"]).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let current = read::<Jobs>(response).await;
+        assert_eq!(current.inc, 2);
+        assert_eq!(current.jobs[1].name(), "Dishes");
+
+        let stored = store.0.get::<Jobs>().unwrap().unwrap();
+        assert_eq!(stored.inc, 2);
+        assert_eq!(stored.jobs[0].name(), "Dinner");
+    }
+
+    #[tokio::test]
+    async fn put_of_a_document_the_server_lacks_needs_no_base() {
+        let store = TestStore::new("put-missing");
+
+        let response = put_jobs(&store, 3, &["Dinner"]).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert!(store.0.get::<Jobs>().unwrap().is_none());
+    }
+
+    async fn post_ops(store: &TestStore, base: usize, ops: Vec<PeopleOp>) -> Response {
+        let mut log = ChangeLog::new(base);
+        log.extend(ops);
+        post_people_ops(store.state(), Json(log)).await.unwrap()
+    }
+
+    fn add(name: &str) -> PeopleOp {
+        PeopleOp::Add(Person::new(name, Ability::new("Adult")))
+    }
+
+    #[tokio::test]
+    async fn ops_are_applied_to_the_current_version() {
+        let store = TestStore::new("ops-applied");
+
+        let response = post_ops(&store, 0, vec![add("Ann"), add("Bob")]).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let people = read::<People>(response).await;
+        assert_eq!(people.inc, 1);
+        assert_eq!(people.people.len(), 2);
+
+        let response = post_ops(&store, 1, vec![PeopleOp::Delete(0)]).await;
+        let people = read::<People>(response).await;
+        assert_eq!(people.inc, 2);
+        assert_eq!(people.people[0].name(), "Bob");
+    }
+
+    #[tokio::test]
+    async fn ops_on_an_old_version_are_a_conflict() {
+        let store = TestStore::new("ops-conflict");
+        post_ops(&store, 0, vec![add("Ann")]).await;
+        post_ops(&store, 1, vec![add("Bob")]).await;
+
+        let response = post_ops(&store, 1, vec![PeopleOp::Delete(0)]).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let current = read::<People>(response).await;
+        assert_eq!(current.inc, 2);
+        assert_eq!(current.people.len(), 2);
+        assert_eq!(store.0.get::<People>().unwrap().unwrap().people.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn ops_on_someone_missing_change_nothing() {
+        let store = TestStore::new("ops-missing");
+        post_ops(&store, 0, vec![add("Ann")]).await;
+
+        let response = post_ops(&store, 1, vec![add("Bob"), PeopleOp::Delete(5)]).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let stored = store.0.get::<People>().unwrap().unwrap();
+        assert_eq!(stored.inc, 1);
+        assert_eq!(stored.people.len(), 1);
+    }
+}
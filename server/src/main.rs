@@ -0,0 +1,84 @@
+//! Self-hostable Kitchen Patrol server
+//!
+//! Serves the built web app and a JSON API under `/api` for the people, jobs, age groups,
+//!  settings and calculated week, so every device pointed at it shares one roster. Each
+//!  collection is kept as a JSON file in the data directory.
+
+mod api;
+mod store;
+
+use std::error::Error;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process;
+
+use axum::http::HeaderValue;
+use axum::Router;
+use clap::Parser;
+use tower_http::cors::{Any, CorsLayer};
+use tower_http::services::ServeDir;
+
+use crate::store::FileStore;
+
+#[derive(Parser)]
+#[command(
+    name = "kp-chart-server",
+    version,
+    about = "Serves the Kitchen Patrol web app with shared storage"
+)]
+struct Args {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+
+    /// Directory the roster is stored in, created if it doesn't exist
+    #[arg(long, default_value = "kp-chart-data")]
+    data: PathBuf,
+
+    /// The built web app, from `make build`
+    #[arg(long = "static", default_value = "dist")]
+    static_dir: PathBuf,
+
+    /// Other origins allowed to use the API, e.g. where the web app is deployed
+    #[arg(long)]
+    allow_origin: Vec<String>,
+}
+
+async fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let mut api = api::router(FileStore::open(args.data)?);
+    if !args.allow_origin.is_empty() {
+        let origins = args
+            .allow_origin
+            .iter()
+            .map(|o| o.parse::<HeaderValue>())
+            .collect::<Result<Vec<_>, _>>()?;
+        api = api.layer(
+            CorsLayer::new()
+                .allow_origin(origins)
+                .allow_methods(Any)
+                .allow_headers(Any),
+        );
+    }
+
+    let app = Router::new()
+        .nest("/api", api)
+        .fallback_service(ServeDir::new(args.static_dir));
+
+    let listener = tokio::net::TcpListener::bind(args.listen).await?;
+    println!("serving on http://{}", listener.local_addr()?);
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(err) = run(Args::parse()).await {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
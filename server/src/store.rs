@@ -0,0 +1,172 @@
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use kp_chart::data::{Abilities, Job, Makeup, Person, Settings, Week};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// A document the web app keeps in local storage, stored on the server in the same form
+pub trait Collection: Serialize + DeserializeOwned + Send + 'static {
+    /// Name in the API path and of the file it's stored in
+    const NAME: &'static str;
+
+    /// The version, set by the server each time the document is saved
+    fn inc(&self) -> usize;
+
+    fn set_inc(&mut self, inc: usize);
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct People {
+    pub inc: usize,
+    pub people: Vec<Person>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Jobs {
+    pub inc: usize,
+    pub jobs: Vec<Job>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AbilityGroups {
+    pub inc: usize,
+    pub abilities: Abilities,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ChartSettings {
+    pub inc: usize,
+    pub settings: Settings,
+}
+
+/// The calculated week with its swaps
+#[derive(Serialize, Deserialize)]
+pub struct Schedule {
+    pub inc: usize,
+    /// Fingerprints of the roster the week was calculated from, only the web app reads these
+    pub sources: serde_json::Value,
+    pub week: Week,
+    /// The people in the order the week was calculated with, when it was balanced with past charts
+    #[serde(default)]
+    pub scheduled: Option<Vec<Person>>,
+    /// Jobs missed in the chart before, made up in this one
+    #[serde(default)]
+    pub owed: Vec<Makeup>,
+    /// The owed jobs there wasn't a place for
+    #[serde(default)]
+    pub unplaced: Vec<Makeup>,
+}
+
+macro_rules! collection {
+    ($ty:ty, $name:expr) => {
+        impl Collection for $ty {
+            const NAME: &'static str = $name;
+
+            fn inc(&self) -> usize {
+                self.inc
+            }
+
+            fn set_inc(&mut self, inc: usize) {
+                self.inc = inc;
+            }
+        }
+    };
+}
+
+collection!(People, "people");
+collection!(Jobs, "jobs");
+collection!(AbilityGroups, "abilities");
+collection!(ChartSettings, "settings");
+collection!(Schedule, "week");
+
+#[derive(Debug)]
+pub enum StoreError {
+    Io(io::Error),
+    Json(serde_json::Error),
+}
+
+impl Display for StoreError {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            StoreError::Io(err) => write!(fmt, "storage failed: {}", err),
+            StoreError::Json(err) => write!(fmt, "stored document is invalid: {}", err),
+        }
+    }
+}
+
+impl From<io::Error> for StoreError {
+    fn from(err: io::Error) -> Self {
+        StoreError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for StoreError {
+    fn from(err: serde_json::Error) -> Self {
+        StoreError::Json(err)
+    }
+}
+
+/// Keeps each collection as a JSON file in a directory
+pub struct FileStore {
+    dir: PathBuf,
+    /// Writes replace whole files, this keeps them from interleaving
+    write: Mutex<()>,
+}
+
+impl FileStore {
+    pub fn open(dir: PathBuf) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            write: Mutex::new(()),
+        })
+    }
+
+    #[cfg(test)]
+    pub fn dir(&self) -> &std::path::Path {
+        &self.dir
+    }
+
+    fn path<C: Collection>(&self) -> PathBuf {
+        self.dir.join(format!("{}.json", C::NAME))
+    }
+
+    /// The stored document, None if it's never been saved
+    pub fn get<C: Collection>(&self) -> Result<Option<C>, StoreError> {
+        match fs::read(self.path::<C>()) {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Reads, changes and writes back a document without any other writes in between
+    ///
+    /// `f` returns the document to write, if any, along with the result.
+    pub fn update<C, R, F>(&self, f: F) -> Result<R, StoreError>
+    where
+        C: Collection,
+        F: FnOnce(Option<C>) -> (Option<C>, R),
+    {
+        let _write = self.write.lock().unwrap_or_else(|e| e.into_inner());
+        let (document, result) = f(self.get()?);
+        if let Some(document) = document {
+            self.write_file(&document)?;
+        }
+        Ok(result)
+    }
+
+    fn write_file<C: Collection>(&self, document: &C) -> Result<(), StoreError> {
+        let data = serde_json::to_vec_pretty(document)?;
+        let path = self.path::<C>();
+        let tmp = path.with_extension("json.tmp");
+
+        fs::write(&tmp, data)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::slice::Iter;
+
+/// An age or ability group, e.g. "Adult" or "Toddler", identified by its name
+///
+/// Serialized as the bare name, which matches what the old fixed set of groups stored.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Ability(String);
+
+impl Ability {
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Ability(name.into())
+    }
+
+    pub fn to_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for Ability {
+    fn default() -> Self {
+        Ability::new("Adult")
+    }
+}
+
+impl Display for Ability {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", self.0)
+    }
+}
+
+/// The user configurable list of ability groups, in display order
+///
+/// The first group is the one that gets its rotation offset every day when scheduling, see
+///  `calculate`, so it should be the group that covers the major jobs.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Abilities(Vec<Ability>);
+
+impl Abilities {
+    pub fn new(abilities: Vec<Ability>) -> Self {
+        Abilities(abilities)
+    }
+
+    pub fn iter(&self) -> Iter<'_, Ability> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&Ability> {
+        self.0.get(idx)
+    }
+
+    pub fn first(&self) -> Option<&Ability> {
+        self.0.first()
+    }
+
+    pub fn contains(&self, ability: &Ability) -> bool {
+        self.0.contains(ability)
+    }
+
+    /// Adds the group at the end, returns false if it already exists
+    pub fn push(&mut self, ability: Ability) -> bool {
+        if self.contains(&ability) {
+            return false;
+        }
+
+        self.0.push(ability);
+        true
+    }
+
+    pub fn remove(&mut self, idx: usize) -> Option<Ability> {
+        if idx < self.0.len() {
+            Some(self.0.remove(idx))
+        } else {
+            None
+        }
+    }
+
+    /// Moves the group at idx one place earlier in the order
+    pub fn move_up(&mut self, idx: usize) -> bool {
+        if idx == 0 || idx >= self.0.len() {
+            return false;
+        }
+
+        self.0.swap(idx - 1, idx);
+        true
+    }
+}
+
+impl Default for Abilities {
+    fn default() -> Self {
+        Abilities(vec![
+            Ability::new("Adult"),
+            Ability::new("Teen"),
+            Ability::new("Child"),
+        ])
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+use crate::data::{Person, Week};
+
+/// Weeks that are over, as they were done with any trades, oldest first
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Archive {
+    #[serde(default)]
+    weeks: Vec<Week>,
+}
+
+impl Archive {
+    pub fn weeks(&self) -> &[Week] {
+        &self.weeks
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weeks.is_empty()
+    }
+
+    /// true if a week starting on the same day is already archived
+    pub fn contains(&self, week: &Week) -> bool {
+        self.weeks.iter().any(|w| w.start() == week.start())
+    }
+
+    /// Adds the week in date order, replacing one that starts on the same day
+    pub fn add(&mut self, week: Week) {
+        match self.weeks.iter().position(|w| w.start() >= week.start()) {
+            Some(idx) if self.weeks[idx].start() == week.start() => self.weeks[idx] = week,
+            Some(idx) => self.weeks.insert(idx, week),
+            None => self.weeks.push(week),
+        }
+    }
+
+    pub fn remove(&mut self, idx: usize) -> Option<Week> {
+        if idx < self.weeks.len() {
+            Some(self.weeks.remove(idx))
+        } else {
+            None
+        }
+    }
+
+    /// Everyone with a job in any of the weeks, in the order they first appear, leaving out the
+    ///  placeholders for unfilled slots
+    pub fn people(&self) -> Vec<Person> {
+        let mut people = Vec::<Person>::new();
+        for week in self.weeks.iter() {
+            for day in week.days() {
+                for (_, workers) in day.jobs() {
+                    for worker in workers.iter().filter(|w| !w.is_placeholder()) {
+                        if !people.iter().any(|p| p.name() == worker.name()) {
+                            people.push(worker.clone());
+                        }
+                    }
+                }
+            }
+        }
+        people
+    }
+}
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+
+use crate::data::{Ability, Person, Tags};
+
+/// An edit to the list of people, recorded so it can be replayed on another copy of the list
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PeopleOp {
+    Add(Person),
+    Delete(usize),
+    SetName(usize, String),
+    SetAbility(usize, Ability),
+    SetTags(usize, Tags),
+    /// Everyone replaced at once, e.g. from an import
+    Replace(Vec<Person>),
+}
+
+impl PeopleOp {
+    /// Applies the edit, returns false if the person it refers to doesn't exist
+    pub fn apply(&self, people: &mut Vec<Person>) -> bool {
+        match self {
+            PeopleOp::Add(person) => people.push(person.clone()),
+            PeopleOp::Delete(idx) if *idx < people.len() => {
+                people.remove(*idx);
+            }
+            PeopleOp::SetName(idx, name) if *idx < people.len() => {
+                people[*idx].set_name(name.clone())
+            }
+            PeopleOp::SetAbility(idx, ability) if *idx < people.len() => {
+                people[*idx].set_ability(ability.clone())
+            }
+            PeopleOp::SetTags(idx, tags) if *idx < people.len() => {
+                people[*idx].set_tags(tags.clone())
+            }
+            PeopleOp::Replace(replacement) => *people = replacement.clone(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Edits made on top of a known version of a shared copy, waiting to be sent to it
+///
+/// The shared copy only accepts the edits if it's still at `base`, otherwise someone else changed
+///  it in the meantime and the two need to be reconciled.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ChangeLog<Op> {
+    pub base: usize,
+    pub ops: Vec<Op>,
+}
+
+impl<Op> Default for ChangeLog<Op> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl<Op> ChangeLog<Op> {
+    pub fn new(base: usize) -> Self {
+        Self {
+            base,
+            ops: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn extend<I: IntoIterator<Item = Op>>(&mut self, ops: I) {
+        self.ops.extend(ops);
+    }
+
+    /// Drops the first `count` edits once the shared copy has accepted them as version `base`
+    pub fn accepted(&mut self, count: usize, base: usize) {
+        self.ops.drain(..count.min(self.ops.len()));
+        self.base = base;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::parse_tags;
+
+    fn names(people: &[Person]) -> Vec<&str> {
+        people.iter().map(Person::name).collect()
+    }
+
+    #[test]
+    fn applies_each_edit() {
+        let adult = || Ability::new("Adult");
+        let mut people = vec![Person::new("Ann", adult())];
+
+        assert!(PeopleOp::Add(Person::new("Bob", adult())).apply(&mut people));
+        assert!(PeopleOp::SetName(0, "Amy".to_string()).apply(&mut people));
+        assert!(PeopleOp::SetAbility(1, Ability::new("Teen")).apply(&mut people));
+        assert!(PeopleOp::SetTags(1, parse_tags("driver")).apply(&mut people));
+        assert_eq!(names(&people), ["Amy", "Bob"]);
+        assert_eq!(people[1].ability(), &Ability::new("Teen"));
+        assert_eq!(people[1].tags(), &parse_tags("driver"));
+
+        assert!(PeopleOp::Delete(0).apply(&mut people));
+        assert_eq!(names(&people), ["Bob"]);
+
+        assert!(PeopleOp::Replace(vec![Person::new("Cat", adult())]).apply(&mut people));
+        assert_eq!(names(&people), ["Cat"]);
+    }
+
+    #[test]
+    fn edits_to_someone_missing_do_nothing() {
+        let mut people = vec![Person::new("Ann", Ability::new("Adult"))];
+        assert!(!PeopleOp::Delete(1).apply(&mut people));
+        assert!(!PeopleOp::SetName(3, "Bob".to_string()).apply(&mut people));
+        assert_eq!(names(&people), ["Ann"]);
+    }
+
+    #[test]
+    fn accepted_edits_are_dropped() {
+        let mut log = ChangeLog::new(4);
+        log.extend([
+            PeopleOp::Delete(0),
+            PeopleOp::Delete(1),
+            PeopleOp::Delete(2),
+        ]);
+
+        log.accepted(2, 5);
+        assert_eq!(log.base, 5);
+        assert_eq!(log.ops, [PeopleOp::Delete(2)]);
+
+        log.accepted(3, 6);
+        assert!(log.is_empty());
+    }
+}
@@ -1,57 +1,59 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt::{self, Display, Formatter};
-use std::iter::Cycle;
-use std::slice::Iter;
 
-use crate::data::{Ability, Job, Person};
+use crate::data::explanation::Tracer;
+use crate::data::{Ability, Job, Person, Rotation};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Day {
     name: String,
+    date: NaiveDate,
     jobs: Vec<(Job, Vec<Person>)>,
 }
 
 impl Day {
     pub fn new(
         name: String,
+        date: NaiveDate,
         jobs: Vec<Job>,
-        children: &mut Cycle<Iter<Person>>,
-        teens: &mut Cycle<Iter<Person>>,
-        adults: &mut Cycle<Iter<Person>>,
+        rotations: &mut BTreeMap<Ability, Rotation>,
+    ) -> Self {
+        Self::traced(name, date, jobs, rotations, None)
+    }
+
+    /// Fills the day's jobs, recording why each slot was filled when there's a tracer
+    pub(crate) fn traced(
+        name: String,
+        date: NaiveDate,
+        jobs: Vec<Job>,
+        rotations: &mut BTreeMap<Ability, Rotation>,
+        mut tracer: Option<&mut Tracer>,
     ) -> Self {
         let mut day_jobs = jobs
-            .clone()
             .into_iter()
             .map(|j| (j, Vec::<Person>::new()))
             .collect::<Vec<_>>();
 
-        // pass through all children jobs first
-        for (job, ref mut workers) in day_jobs.iter_mut() {
-            for ability in job.people().iter() {
-                match *ability {
-                    Ability::Child => workers.push(
-                        children
-                            .next()
-                            .cloned()
-                            .unwrap_or_else(|| Person::new("No Child Here", Ability::Child)),
-                    ),
-                    Ability::Teen => workers.push(
-                        teens
-                            .next()
-                            .cloned()
-                            .unwrap_or_else(|| Person::new("No Teen Here", Ability::Teen)),
-                    ),
-                    Ability::Adult => workers.push(
-                        adults
-                            .next()
-                            .cloned()
-                            .unwrap_or_else(|| Person::new("No Adult Here", Ability::Adult)),
-                    ),
+        for (job_idx, (job, ref mut workers)) in day_jobs.iter_mut().enumerate() {
+            for (slot_idx, slot) in job.people().iter().enumerate() {
+                let ability = slot.ability();
+                if let Some(tracer) = tracer.as_deref_mut() {
+                    tracer.slot(job_idx, job.name(), slot_idx, slot, rotations.get(ability));
                 }
+                workers.push(
+                    rotations
+                        .get_mut(ability)
+                        .and_then(|r| r.next_for(slot))
+                        .unwrap_or_else(|| Person::placeholder(ability)),
+                );
             }
         }
 
         Self {
             name,
+            date,
             jobs: day_jobs,
         }
     }
@@ -60,12 +62,26 @@ impl Day {
         &self.name
     }
 
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
     pub fn jobs(&self) -> &[(Job, Vec<Person>)] {
         &self.jobs
     }
 
-    pub fn get_job_people(&self, job: usize) -> &[Person] {
-        &self.jobs[job].1
+    pub(crate) fn jobs_mut(&mut self) -> &mut [(Job, Vec<Person>)] {
+        &mut self.jobs
+    }
+
+    /// The index of the job in this day's jobs, None if the job isn't done on this day
+    pub fn find_job(&self, job: &Job) -> Option<usize> {
+        self.jobs.iter().position(|(j, _)| j.name() == job.name())
+    }
+
+    /// The people on the job, None if the job isn't done on this day
+    pub fn get_job_people(&self, job: &Job) -> Option<&[Person]> {
+        self.find_job(job).map(|idx| self.jobs[idx].1.as_slice())
     }
 }
 
@@ -76,7 +92,7 @@ impl Display for Day {
             for person in people.iter() {
                 write!(fmt, "{}, ", person)?;
             }
-            writeln!(fmt, "")?;
+            writeln!(fmt)?;
         }
         Ok(())
     }
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::data::Job;
+
+/// Changes to the usual jobs for a single day, e.g. no dinner prep on pizza night
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct DayOverride {
+    /// names of the regular jobs that aren't done on the day
+    #[serde(default)]
+    skip: BTreeSet<String>,
+    /// one-off jobs only done on the day
+    #[serde(default)]
+    extra: Vec<Job>,
+}
+
+impl DayOverride {
+    pub fn skip(&self) -> &BTreeSet<String> {
+        &self.skip
+    }
+
+    pub fn extra(&self) -> &[Job] {
+        &self.extra
+    }
+
+    pub fn is_skipped(&self, job: &Job) -> bool {
+        self.skip.contains(job.name())
+    }
+
+    /// Skips the job if it was being done, otherwise puts it back on the day
+    pub fn toggle_skip(&mut self, job: &str) {
+        if !self.skip.remove(job) {
+            self.skip.insert(job.to_string());
+        }
+    }
+
+    /// Adds a one-off job, returns false if a regular or one-off job already has its name
+    ///
+    /// Jobs on a day are found by name, so two with the same name would be mixed up.
+    pub fn add_extra(&mut self, job: Job, regular: &[Job]) -> bool {
+        let key = |job: &Job| job.name().trim().to_lowercase();
+        if regular
+            .iter()
+            .chain(&self.extra)
+            .any(|j| key(j) == key(&job))
+        {
+            return false;
+        }
+        self.extra.push(job);
+        true
+    }
+
+    pub fn remove_extra(&mut self, idx: usize) -> Option<Job> {
+        if idx < self.extra.len() {
+            Some(self.extra.remove(idx))
+        } else {
+            None
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.skip.is_empty() && self.extra.is_empty()
+    }
+
+    /// Removes the skipped jobs and adds the extra ones to the day's regular jobs
+    pub fn apply(&self, jobs: Vec<Job>) -> Vec<Job> {
+        jobs.into_iter()
+            .filter(|j| !self.is_skipped(j))
+            .chain(self.extra.iter().cloned())
+            .collect()
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+
+use crate::data::{Ability, Assignment, Person, Rotation, Slot, Tags};
+
+/// Why the scheduler filled a slot the way it did
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Explanation {
+    pub assignment: Assignment,
+    pub job: String,
+    pub slot: Slot,
+    /// Everyone in the slot's group, in the order they were in line when it was filled
+    pub candidates: Vec<Candidate>,
+    /// Places the group's line was moved on without anyone being assigned, since the day before
+    pub advanced: usize,
+}
+
+impl Explanation {
+    /// Who got the slot, None if nobody in the group could do it
+    pub fn chosen(&self) -> Option<&Candidate> {
+        self.candidates.iter().find(|c| c.reason == Reason::Chosen)
+    }
+}
+
+/// Someone who could have been put in a slot
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Candidate {
+    pub name: String,
+    /// Where they were in line, starting at 1
+    pub place: usize,
+    /// How many jobs they had in the chart before this one
+    pub jobs_so_far: usize,
+    pub reason: Reason,
+}
+
+/// Why a candidate was or wasn't put in a slot
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Reason {
+    /// First in line of those who can do it
+    Chosen,
+    /// Ahead in line, but doesn't have these tags
+    MissingTags(Tags),
+    /// Behind whoever was chosen
+    LaterInLine,
+}
+
+/// Records an explanation for every slot as the week is calculated
+#[derive(Debug, Default)]
+pub(crate) struct Tracer {
+    day: usize,
+    jobs_so_far: BTreeMap<String, usize>,
+    advanced: BTreeMap<Ability, usize>,
+    explanations: Vec<Explanation>,
+}
+
+impl Tracer {
+    pub(crate) fn start_day(&mut self, day: usize) {
+        self.day = day;
+    }
+
+    /// Records that the group's line was moved on, it's explained on the next day's slots
+    pub(crate) fn advanced(&mut self, ability: &Ability, places: usize) {
+        self.advanced.insert(ability.clone(), places);
+    }
+
+    /// Records the slot about to be filled from the rotation, the same way `Rotation::next_for` picks
+    pub(crate) fn slot(
+        &mut self,
+        job: usize,
+        job_name: &str,
+        slot_idx: usize,
+        slot: &Slot,
+        rotation: Option<&Rotation>,
+    ) {
+        let mut chosen: Option<&Person> = None;
+        let candidates = rotation
+            .into_iter()
+            .flat_map(Rotation::line)
+            .enumerate()
+            .map(|(i, person)| {
+                let reason = if chosen.is_some() {
+                    Reason::LaterInLine
+                } else if slot.accepts(person) {
+                    chosen = Some(person);
+                    Reason::Chosen
+                } else {
+                    Reason::MissingTags(slot.tags().difference(person.tags()).cloned().collect())
+                };
+                Candidate {
+                    name: person.name().to_string(),
+                    place: i + 1,
+                    jobs_so_far: self.jobs_so_far.get(person.name()).copied().unwrap_or(0),
+                    reason,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(chosen) = chosen {
+            *self
+                .jobs_so_far
+                .entry(chosen.name().to_string())
+                .or_default() += 1;
+        }
+
+        self.explanations.push(Explanation {
+            assignment: Assignment {
+                day: self.day,
+                job,
+                slot: slot_idx,
+            },
+            job: job_name.to_string(),
+            slot: slot.clone(),
+            candidates,
+            advanced: if self.day == 0 {
+                0
+            } else {
+                self.advanced.get(slot.ability()).copied().unwrap_or(0)
+            },
+        });
+    }
+
+    pub(crate) fn into_explanations(self) -> Vec<Explanation> {
+        self.explanations
+    }
+}
@@ -0,0 +1,100 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use crate::data::{Assignment, Person, Week};
+
+/// How the jobs of a week, or of several weeks, fall on one person
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Fairness {
+    person: Person,
+    total: usize,
+    /// How many of the jobs were checked off as done
+    done: usize,
+    weeks: usize,
+    jobs: BTreeMap<String, usize>,
+}
+
+impl Fairness {
+    /// The stats for everyone on the roster, including those without any jobs
+    ///
+    /// The placeholders for unfilled slots aren't anyone, so they're left out.
+    pub fn of(week: &Week, people: &[Person]) -> Vec<Fairness> {
+        Self::across(std::slice::from_ref(week), people)
+    }
+
+    /// The stats added up over all the weeks, e.g. every archived chart
+    pub fn across(weeks: &[Week], people: &[Person]) -> Vec<Fairness> {
+        people
+            .iter()
+            .filter(|person| !person.is_placeholder())
+            .map(|person| {
+                let mut jobs = BTreeMap::<String, usize>::new();
+                let mut done = 0;
+                let mut weeks_with_jobs = 0;
+                for week in weeks {
+                    let mut in_week = false;
+                    for (day_idx, day) in week.days().iter().enumerate() {
+                        for (job_idx, (job, workers)) in day.jobs().iter().enumerate() {
+                            for (slot, worker) in workers.iter().enumerate() {
+                                if worker.is_placeholder() || worker.name() != person.name() {
+                                    continue;
+                                }
+
+                                *jobs.entry(job.name().to_string()).or_default() += 1;
+                                in_week = true;
+                                let assignment = Assignment {
+                                    day: day_idx,
+                                    job: job_idx,
+                                    slot,
+                                };
+                                if week.is_done(assignment) {
+                                    done += 1;
+                                }
+                            }
+                        }
+                    }
+                    if in_week {
+                        weeks_with_jobs += 1;
+                    }
+                }
+
+                Fairness {
+                    person: person.clone(),
+                    total: jobs.values().sum(),
+                    done,
+                    weeks: weeks_with_jobs,
+                    jobs,
+                }
+            })
+            .collect()
+    }
+
+    pub fn person(&self) -> &Person {
+        &self.person
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// How many of the jobs were checked off as done
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    /// The number of weeks the person had any job in
+    pub fn weeks(&self) -> usize {
+        self.weeks
+    }
+
+    /// Compares the jobs per week, anyone without any weeks counts as having done none
+    pub fn cmp_per_week(&self, other: &Fairness) -> Ordering {
+        // total / weeks compared without dividing
+        (self.total * other.weeks.max(1)).cmp(&(other.total * self.weeks.max(1)))
+    }
+
+    /// Count of each job the person has, by job name
+    pub fn jobs(&self) -> &BTreeMap<String, usize> {
+        &self.jobs
+    }
+}
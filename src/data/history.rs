@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// Changes that can be undone, and undone changes that can be redone
+///
+/// Only the most recent `limit` changes are kept.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct History<C> {
+    done: Vec<C>,
+    undone: Vec<C>,
+    limit: usize,
+}
+
+impl<C> History<C> {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            done: Vec::new(),
+            undone: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Records a new change, which makes anything undone no longer redoable
+    pub fn push(&mut self, change: C) {
+        self.undone.clear();
+        self.done.push(change);
+        if self.done.len() > self.limit {
+            let extra = self.done.len() - self.limit;
+            self.done.drain(..extra);
+        }
+    }
+
+    /// The change that `undo` would undo
+    pub fn last_done(&self) -> Option<&C> {
+        self.done.last()
+    }
+
+    /// The change that `redo` would redo
+    pub fn last_undone(&self) -> Option<&C> {
+        self.undone.last()
+    }
+
+    /// Moves the last change to the undone list, returning it so it can be reversed
+    pub fn undo(&mut self) -> Option<&C> {
+        let change = self.done.pop()?;
+        self.undone.push(change);
+        self.undone.last()
+    }
+
+    /// Moves the last undone change back, returning it so it can be applied again
+    pub fn redo(&mut self) -> Option<&C> {
+        let change = self.undone.pop()?;
+        self.done.push(change);
+        self.done.last()
+    }
+}
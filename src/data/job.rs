@@ -1,28 +1,54 @@
+use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
 
-use crate::data::Ability;
+use crate::data::{Ability, Person, Recurrence, Tags};
 
-#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Job {
     name: String,
-    people: Vec<Ability>,
+    people: Vec<Slot>,
+    #[serde(default)]
+    recurrence: Recurrence,
 }
 
 impl Job {
-    pub fn new(name: &'static str, people: Vec<Ability>) -> Self {
+    pub fn new<N: Into<String>, S: Into<Slot>>(name: N, people: Vec<S>) -> Self {
         Self {
-            name: name.to_string(),
-            people,
+            name: name.into(),
+            people: people.into_iter().map(Into::into).collect(),
+            recurrence: Recurrence::Daily,
         }
     }
 
+    pub fn with_recurrence(mut self, recurrence: Recurrence) -> Self {
+        self.recurrence = recurrence;
+        self
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
 
-    pub fn people(&self) -> &[Ability] {
+    pub fn people(&self) -> &[Slot] {
         &self.people
     }
+
+    pub fn recurrence(&self) -> &Recurrence {
+        &self.recurrence
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn people_mut(&mut self) -> &mut Vec<Slot> {
+        &mut self.people
+    }
+
+    pub fn set_recurrence(&mut self, recurrence: Recurrence) {
+        self.recurrence = recurrence;
+    }
 }
 
 impl Display for Job {
@@ -30,3 +56,78 @@ impl Display for Job {
         write!(fmt, "{}", self.name)
     }
 }
+
+/// One person's place on a job, they need the ability and every one of the tags
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Slot {
+    ability: Ability,
+    #[serde(default)]
+    tags: Tags,
+}
+
+impl Slot {
+    pub fn new(ability: Ability, tags: Tags) -> Self {
+        Self { ability, tags }
+    }
+
+    pub fn ability(&self) -> &Ability {
+        &self.ability
+    }
+
+    pub fn tags(&self) -> &Tags {
+        &self.tags
+    }
+
+    pub fn set_ability(&mut self, ability: Ability) {
+        self.ability = ability;
+    }
+
+    pub fn set_tags(&mut self, tags: Tags) {
+        self.tags = tags;
+    }
+
+    /// true if the person is of the right ability and has all the required tags
+    pub fn accepts(&self, person: &Person) -> bool {
+        *person.ability() == self.ability && self.tags.is_subset(person.tags())
+    }
+}
+
+impl From<Ability> for Slot {
+    fn from(ability: Ability) -> Self {
+        Self {
+            ability,
+            tags: Tags::new(),
+        }
+    }
+}
+
+impl Display for Slot {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", self.ability)?;
+        for tag in self.tags.iter() {
+            write!(fmt, " +{}", tag)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the `Display` form of a slot, the ability followed by its tags, e.g. "Adult +driver"
+impl FromStr for Slot {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('+').map(str::trim);
+        let ability = parts.next().unwrap_or_default();
+        if ability.is_empty() {
+            return Err(format!("no ability in slot: {}", s));
+        }
+
+        Ok(Self {
+            ability: Ability::new(ability),
+            tags: parts
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+}
@@ -1,9 +1,40 @@
+mod ability;
+mod archive;
+mod change_log;
 mod day;
+mod day_override;
+mod explanation;
+mod fairness;
+mod history;
 mod job;
 mod person;
+mod recurrence;
+mod roster;
+mod rotation;
+mod settings;
+mod share;
+mod tags;
+mod template;
+mod validation;
 mod week;
 
+pub use self::ability::{Abilities, Ability};
+pub use self::archive::Archive;
+pub use self::change_log::{ChangeLog, PeopleOp};
 pub use self::day::Day;
-pub use self::job::Job;
-pub use self::person::{Ability, Person};
-pub use self::week::Week;
+pub use self::day_override::DayOverride;
+pub(crate) use self::explanation::Tracer;
+pub use self::explanation::{Candidate, Explanation, Reason};
+pub use self::fairness::Fairness;
+pub use self::history::History;
+pub use self::job::{Job, Slot};
+pub use self::person::Person;
+pub use self::recurrence::{parse_dates, Recurrence};
+pub use self::roster::Roster;
+pub use self::rotation::Rotation;
+pub use self::settings::Settings;
+pub use self::share::{ShareError, SharedChart};
+pub use self::tags::{parse_tags, tags_to_string, Tags};
+pub use self::template::JobTemplate;
+pub use self::validation::{validate_jobs, validate_people, Invalid, JobErrors, PersonErrors};
+pub use self::week::{Assignment, Makeup, SwapError, Week};
@@ -1,87 +1,69 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 
+use crate::data::{Ability, Tags};
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Person {
     name: String,
     ability: Ability,
+    #[serde(default)]
+    tags: Tags,
+    /// Stands in for a slot nobody could fill, it's not someone on the roster
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    placeholder: bool,
 }
 
 impl Person {
-    pub fn new(name: &'static str, ability: Ability) -> Self {
+    pub fn new<S: Into<String>>(name: S, ability: Ability) -> Self {
         Self {
-            name: name.to_string(),
+            name: name.into(),
             ability,
+            tags: Tags::new(),
+            placeholder: false,
         }
     }
 
-    pub fn name(&self) -> &str {
-        &self.name
-    }
-
-    pub fn ability(&self) -> Ability {
-        self.ability
-    }
-
-    pub fn set_name(&mut self, name: String) {
-        self.name = name;
+    /// Someone to show in a slot nobody in the age group could fill
+    pub fn placeholder(ability: &Ability) -> Self {
+        Self {
+            placeholder: true,
+            ..Self::new(format!("No {} Here", ability), ability.clone())
+        }
     }
 
-    pub fn set_ability(&mut self, ability: Ability) {
-        self.ability = ability;
+    /// true for a stand in for an unfilled slot, rather than someone on the roster
+    pub fn is_placeholder(&self) -> bool {
+        self.placeholder
     }
-}
 
-impl Display for Person {
-    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
-        write!(fmt, "{}", self.name)
+    pub fn name(&self) -> &str {
+        &self.name
     }
-}
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
-pub enum Ability {
-    Adult = 0,
-    Teen = 1,
-    Child = 2,
-}
-
-impl Default for Ability {
-    fn default() -> Self {
-        Ability::Adult
+    pub fn ability(&self) -> &Ability {
+        &self.ability
     }
-}
 
-impl Ability {
-    pub fn enumerate() -> &'static [Ability] {
-        &[Ability::Adult, Ability::Teen, Ability::Child]
+    pub fn tags(&self) -> &Tags {
+        &self.tags
     }
 
-    pub fn to_str(&self) -> &'static str {
-        match self {
-            Ability::Adult => "Adult",
-            Ability::Teen => "Teen",
-            Ability::Child => "Child",
-        }
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
     }
 
-    pub fn from_i32(prim: i32) -> Self {
-        match prim {
-            0 => Ability::Adult,
-            1 => Ability::Teen,
-            2 => Ability::Child,
-            _ => panic!("bad value for Ability: {}", prim),
-        }
+    pub fn set_ability(&mut self, ability: Ability) {
+        self.ability = ability;
     }
-}
 
-impl From<Ability> for i32 {
-    fn from(ability: Ability) -> i32 {
-        ability as i32
+    pub fn set_tags(&mut self, tags: Tags) {
+        self.tags = tags;
     }
 }
 
-impl Display for Ability {
+impl Display for Person {
     fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
-        write!(fmt, "{}", self.to_str())
+        write!(fmt, "{}", self.name)
     }
 }
@@ -0,0 +1,173 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Which days of the chart a job needs doing, displayed in the form `FromStr` reads back
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Recurrence {
+    #[default]
+    Daily,
+    Weekdays(Vec<Weekday>),
+    /// Every n days, counting from the first day of the chart
+    EveryNDays(usize),
+    Dates(Vec<NaiveDate>),
+}
+
+impl Recurrence {
+    /// true if the job happens on the day, day_idx is the day's offset from the start of the chart
+    pub fn occurs(&self, day_idx: usize, date: NaiveDate) -> bool {
+        match self {
+            Recurrence::Daily => true,
+            Recurrence::Weekdays(days) => days.contains(&date.weekday()),
+            Recurrence::EveryNDays(n) => *n != 0 && day_idx.is_multiple_of(*n),
+            Recurrence::Dates(dates) => dates.contains(&date),
+        }
+    }
+
+    /// A short name for the kind of recurrence, ignoring the details
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Recurrence::Daily => "Every day",
+            Recurrence::Weekdays(_) => "Weekdays",
+            Recurrence::EveryNDays(_) => "Every N days",
+            Recurrence::Dates(_) => "Dates",
+        }
+    }
+
+    /// The default recurrence of each kind, in the order they are offered for editing
+    pub fn kinds() -> Vec<Recurrence> {
+        vec![
+            Recurrence::Daily,
+            Recurrence::Weekdays(Vec::new()),
+            Recurrence::EveryNDays(2),
+            Recurrence::Dates(Vec::new()),
+        ]
+    }
+}
+
+impl Display for Recurrence {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Recurrence::Daily => write!(fmt, "Every day"),
+            Recurrence::Weekdays(days) if days.is_empty() => write!(fmt, "Never"),
+            Recurrence::Dates(dates) if dates.is_empty() => write!(fmt, "Never"),
+            Recurrence::Weekdays(days) => {
+                let days = days.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+                write!(fmt, "{}", days.join(", "))
+            }
+            Recurrence::EveryNDays(1) => write!(fmt, "Every day"),
+            Recurrence::EveryNDays(n) => write!(fmt, "Every {} days", n),
+            Recurrence::Dates(dates) => {
+                let dates = dates.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+                write!(fmt, "{}", dates.join(", "))
+            }
+        }
+    }
+}
+
+/// Parses "daily", "every 3 days", a list of weekdays like "Mon, Wed", a list of ISO dates or
+///  "never" for no days at all
+impl FromStr for Recurrence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_lowercase();
+        if lower.is_empty() || lower == "daily" || lower == "every day" {
+            return Ok(Recurrence::Daily);
+        }
+        if lower == "never" {
+            return Ok(Recurrence::Weekdays(Vec::new()));
+        }
+
+        if let Some(every) = lower.strip_prefix("every ") {
+            let n = every.trim_end_matches("days").trim();
+            return match n.parse() {
+                Ok(0) | Err(_) => Err(format!("bad number of days: {}", s)),
+                Ok(n) => Ok(Recurrence::EveryNDays(n)),
+            };
+        }
+
+        let items = lower
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|i| !i.is_empty())
+            .collect::<Vec<_>>();
+        if let Ok(days) = items.iter().map(|i| i.parse()).collect() {
+            return Ok(Recurrence::Weekdays(days));
+        }
+        if let Ok(dates) = items
+            .iter()
+            .map(|i| NaiveDate::parse_from_str(i, "%Y-%m-%d"))
+            .collect()
+        {
+            return Ok(Recurrence::Dates(dates));
+        }
+
+        Err(format!("not a recurrence: {}", s))
+    }
+}
+
+/// Parses a comma separated list of ISO dates, e.g. "2020-07-01, 2020-07-04", skipping bad ones
+pub fn parse_dates(dates: &str) -> Vec<NaiveDate> {
+    dates
+        .split(',')
+        .filter_map(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2020, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_each_kind() {
+        assert_eq!("".parse(), Ok(Recurrence::Daily));
+        assert_eq!("Daily".parse(), Ok(Recurrence::Daily));
+        assert_eq!("every 3 days".parse(), Ok(Recurrence::EveryNDays(3)));
+        assert_eq!(
+            "Mon, wed".parse(),
+            Ok(Recurrence::Weekdays(vec![Weekday::Mon, Weekday::Wed]))
+        );
+        assert_eq!(
+            "2020-07-01, 2020-07-04".parse(),
+            Ok(Recurrence::Dates(vec![date(7, 1), date(7, 4)]))
+        );
+        assert_eq!("never".parse(), Ok(Recurrence::Weekdays(Vec::new())));
+    }
+
+    #[test]
+    fn rejects_no_days_between() {
+        assert!("every 0 days".parse::<Recurrence>().is_err());
+        assert!("every few days".parse::<Recurrence>().is_err());
+        assert!("sometimes".parse::<Recurrence>().is_err());
+    }
+
+    #[test]
+    fn displays_what_it_parses() {
+        for recurrence in [
+            Recurrence::Daily,
+            Recurrence::EveryNDays(3),
+            Recurrence::Weekdays(vec![Weekday::Tue, Weekday::Sat]),
+            Recurrence::Weekdays(Vec::new()),
+            Recurrence::Dates(vec![date(7, 1), date(7, 4)]),
+        ] {
+            assert_eq!(recurrence.to_string().parse(), Ok(recurrence));
+        }
+        assert_eq!(Recurrence::Dates(Vec::new()).to_string(), "Never");
+    }
+
+    #[test]
+    fn occurs() {
+        // 2020-07-01 was a Wednesday
+        assert!(Recurrence::Daily.occurs(5, date(7, 6)));
+        assert!(Recurrence::EveryNDays(2).occurs(2, date(7, 3)));
+        assert!(!Recurrence::EveryNDays(2).occurs(1, date(7, 2)));
+        assert!(!Recurrence::EveryNDays(0).occurs(0, date(7, 1)));
+        assert!(Recurrence::Weekdays(vec![Weekday::Wed]).occurs(0, date(7, 1)));
+        assert!(!Recurrence::Dates(vec![date(7, 4)]).occurs(0, date(7, 1)));
+    }
+}
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+use crate::data::{Abilities, Job, Person, Settings, Week};
+
+/// Everything a chart is calculated from, this is the format charts are exported and saved in
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Roster {
+    #[serde(default)]
+    pub people: Vec<Person>,
+    #[serde(default)]
+    pub jobs: Vec<Job>,
+    #[serde(default)]
+    pub abilities: Abilities,
+    #[serde(default)]
+    pub settings: Settings,
+}
+
+impl Roster {
+    pub fn calculate(&self) -> Week {
+        crate::calculate(
+            &self.settings,
+            &self.abilities,
+            self.jobs.clone(),
+            self.people.clone(),
+        )
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::VecDeque;
+
+use crate::data::{Person, Slot};
+
+/// Round-robin queue of all the people sharing an ability
+///
+/// Whoever gets assigned moves to the back of the queue. As long as no slot requires tags this
+///  is the same as cycling through the people, otherwise the first person in line that has the
+///  tags is taken and everyone they passed keeps their place.
+#[derive(Clone, Debug)]
+pub struct Rotation {
+    queue: VecDeque<Person>,
+}
+
+impl Rotation {
+    pub fn new<I: IntoIterator<Item = Person>>(people: I) -> Self {
+        Self {
+            queue: people.into_iter().collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Everyone in the rotation, first in line first
+    pub fn line(&self) -> impl Iterator<Item = &Person> {
+        self.queue.iter()
+    }
+
+    /// Takes the next person that can fill the slot, None if nobody in the rotation can
+    pub fn next_for(&mut self, slot: &Slot) -> Option<Person> {
+        let idx = self.queue.iter().position(|p| slot.accepts(p))?;
+        let person = self.queue.remove(idx)?;
+        self.queue.push_back(person.clone());
+        Some(person)
+    }
+
+    /// Moves the person at the front of the line to the back without assigning them
+    pub fn skip(&mut self) {
+        if let Some(person) = self.queue.pop_front() {
+            self.queue.push_back(person);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{parse_tags, Ability};
+
+    fn adult(name: &str) -> Person {
+        Person::new(name, Ability::new("Adult"))
+    }
+
+    fn names(rotation: &Rotation) -> Vec<&str> {
+        rotation.line().map(Person::name).collect()
+    }
+
+    #[test]
+    fn takes_everyone_in_turn() {
+        let mut rotation = Rotation::new(vec![adult("Ann"), adult("Bob"), adult("Cat")]);
+        let slot = Slot::from(Ability::new("Adult"));
+
+        let taken = (0..4)
+            .map(|_| rotation.next_for(&slot).unwrap().name().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(taken, ["Ann", "Bob", "Cat", "Ann"]);
+    }
+
+    #[test]
+    fn passed_people_keep_their_place() {
+        let mut driver = adult("Bob");
+        driver.set_tags(parse_tags("driver"));
+        let mut rotation = Rotation::new(vec![adult("Ann"), driver, adult("Cat")]);
+        let slot = "Adult +driver".parse::<Slot>().unwrap();
+
+        assert_eq!(rotation.next_for(&slot).unwrap().name(), "Bob");
+        assert_eq!(names(&rotation), ["Ann", "Cat", "Bob"]);
+    }
+
+    #[test]
+    fn nobody_for_a_slot_they_cant_fill() {
+        let mut rotation = Rotation::new(vec![adult("Ann")]);
+        let slot = Slot::from(Ability::new("Teen"));
+
+        assert_eq!(rotation.next_for(&slot), None);
+        assert_eq!(names(&rotation), ["Ann"]);
+    }
+
+    #[test]
+    fn skip_moves_the_front_to_the_back() {
+        let mut rotation = Rotation::new(vec![adult("Ann"), adult("Bob")]);
+        rotation.skip();
+        assert_eq!(names(&rotation), ["Bob", "Ann"]);
+    }
+}
@@ -0,0 +1,82 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::data::DayOverride;
+
+/// Chart wide settings, when the chart starts, how many days it covers and any special days
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    start: NaiveDate,
+    num_days: usize,
+    #[serde(default)]
+    overrides: BTreeMap<NaiveDate, DayOverride>,
+    /// Whether people who did fewer jobs in archived charts are put first in line
+    #[serde(default)]
+    balance_history: bool,
+}
+
+impl Settings {
+    pub fn new(start: NaiveDate, num_days: usize) -> Self {
+        Self {
+            start,
+            num_days,
+            overrides: BTreeMap::new(),
+            balance_history: false,
+        }
+    }
+
+    pub fn start(&self) -> NaiveDate {
+        self.start
+    }
+
+    pub fn num_days(&self) -> usize {
+        self.num_days
+    }
+
+    pub fn set_start(&mut self, start: NaiveDate) {
+        self.start = start;
+    }
+
+    pub fn set_num_days(&mut self, num_days: usize) {
+        self.num_days = num_days;
+    }
+
+    pub fn balance_history(&self) -> bool {
+        self.balance_history
+    }
+
+    pub fn set_balance_history(&mut self, balance_history: bool) {
+        self.balance_history = balance_history;
+    }
+
+    pub fn day_override(&self, date: NaiveDate) -> Option<&DayOverride> {
+        self.overrides.get(&date)
+    }
+
+    /// Edits the override for the date, dropping it again if f leaves it empty
+    pub fn update_override<R, F: FnOnce(&mut DayOverride) -> R>(
+        &mut self,
+        date: NaiveDate,
+        f: F,
+    ) -> R {
+        let day_override = self.overrides.entry(date).or_default();
+        let result = f(day_override);
+        if day_override.is_empty() {
+            self.overrides.remove(&date);
+        }
+        result
+    }
+
+    /// All the dates covered by the chart, in order
+    pub fn dates(&self) -> impl Iterator<Item = NaiveDate> {
+        let start = self.start;
+        (0..self.num_days).map(move |i| start + Duration::days(i as i64))
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::new(NaiveDate::default(), 5)
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::BTreeSet;
+use std::fmt::{self, Display, Formatter};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::data::{Assignment, Person, Roster, Week};
+
+/// Largest chart a link is unpacked to, so a bad link can't exhaust memory
+const MAX_SHARED_SIZE: usize = 1024 * 1024;
+
+/// A chart small enough to put in a link, what it's calculated from along with any trades
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SharedChart {
+    pub roster: Roster,
+    #[serde(default)]
+    pub swaps: Vec<(Assignment, Assignment)>,
+    #[serde(default)]
+    pub makeups: Vec<(Assignment, Person)>,
+    #[serde(default)]
+    pub done: BTreeSet<Assignment>,
+}
+
+/// Why a shared chart couldn't be read
+#[derive(Debug)]
+pub enum ShareError {
+    Encoding(base64::DecodeError),
+    Compression,
+    Json(serde_json::Error),
+}
+
+impl Display for ShareError {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            ShareError::Encoding(err) => write!(fmt, "the link is damaged: {}", err),
+            ShareError::Compression => write!(fmt, "the link is damaged or too large"),
+            ShareError::Json(err) => write!(fmt, "the link isn't a chart: {}", err),
+        }
+    }
+}
+
+impl SharedChart {
+    pub fn new(roster: Roster, week: &Week) -> Self {
+        Self {
+            roster,
+            swaps: week.swaps().to_vec(),
+            makeups: week.makeups().to_vec(),
+            done: week.done().clone(),
+        }
+    }
+
+    /// Shares a chart calculated from `scheduled`, the people in the order they were balanced to
+    ///
+    /// The past charts aren't in the link, so the people go in that order with balancing off.
+    pub fn balanced(mut roster: Roster, scheduled: Option<Vec<Person>>, week: &Week) -> Self {
+        if let Some(scheduled) = scheduled {
+            roster.people = scheduled;
+        }
+        roster.settings.set_balance_history(false);
+        Self::new(roster, week)
+    }
+
+    /// The chart as it was shared, the roster calculated again and the makeups and trades replayed
+    pub fn week(&self) -> Week {
+        let mut week = self.roster.calculate();
+        week.replay_makeups(&self.makeups);
+        for (a, b) in self.swaps.iter() {
+            // the same roster always calculates the same week, so these only fail on a bad link
+            week.swap(*a, *b).ok();
+        }
+        for assignment in self.done.iter() {
+            if !week.is_done(*assignment) {
+                week.toggle_done(*assignment).ok();
+            }
+        }
+        week
+    }
+
+    /// Compressed and URL safe, for a link's fragment
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).expect("charts are always serializable");
+        URL_SAFE_NO_PAD.encode(miniz_oxide::deflate::compress_to_vec(&json, 9))
+    }
+
+    pub fn decode(encoded: &str) -> Result<Self, ShareError> {
+        let compressed = URL_SAFE_NO_PAD
+            .decode(encoded.trim())
+            .map_err(ShareError::Encoding)?;
+        let json = miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, MAX_SHARED_SIZE)
+            .map_err(|_| ShareError::Compression)?;
+        serde_json::from_slice(&json).map_err(ShareError::Json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Ability, Job, Settings};
+    use chrono::NaiveDate;
+
+    fn roster() -> Roster {
+        let adult = || Ability::new("Adult");
+        Roster {
+            people: vec![
+                Person::new("Ann", adult()),
+                Person::new("Bob", adult()),
+                Person::new("Cat", adult()),
+            ],
+            jobs: vec![Job::new("Dinner", vec![adult(); 2])],
+            settings: Settings::new(NaiveDate::from_ymd_opt(2020, 7, 1).unwrap(), 3),
+            ..Roster::default()
+        }
+    }
+
+    #[test]
+    fn a_link_brings_back_the_same_chart() {
+        let mut week = roster().calculate();
+        let (a, b) = (
+            Assignment {
+                day: 0,
+                job: 0,
+                slot: 0,
+            },
+            Assignment {
+                day: 1,
+                job: 0,
+                slot: 1,
+            },
+        );
+        week.swap(a, b).unwrap();
+        week.toggle_done(b).unwrap();
+
+        let shared = SharedChart::new(roster(), &week);
+        let decoded = SharedChart::decode(&shared.encode()).unwrap();
+        assert_eq!(decoded, shared);
+
+        let again = decoded.week();
+        assert_eq!(again.to_rows(), week.to_rows());
+        assert_eq!(again.swaps(), week.swaps());
+        assert_eq!(again.done(), week.done());
+    }
+
+    #[test]
+    fn a_damaged_link_is_an_error() {
+        assert!(matches!(
+            SharedChart::decode("not a link!"),
+            Err(ShareError::Encoding(_))
+        ));
+        assert!(matches!(
+            SharedChart::decode("bm90IGEgY2hhcnQ"),
+            Err(ShareError::Compression)
+        ));
+        let not_a_chart = URL_SAFE_NO_PAD.encode(miniz_oxide::deflate::compress_to_vec(b"[]", 9));
+        assert!(matches!(
+            SharedChart::decode(&not_a_chart),
+            Err(ShareError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn a_balanced_chart_is_shared_in_its_balanced_order() {
+        let mut roster = roster();
+        roster.settings.set_balance_history(true);
+        let mut scheduled = roster.people.clone();
+        scheduled.rotate_left(1);
+        let week = crate::calculate(
+            &roster.settings,
+            &roster.abilities,
+            roster.jobs.clone(),
+            scheduled.clone(),
+        );
+
+        let shared = SharedChart::balanced(roster, Some(scheduled), &week);
+        assert!(!shared.roster.settings.balance_history());
+        assert_eq!(shared.week().to_rows(), week.to_rows());
+    }
+}
@@ -0,0 +1,21 @@
+use std::collections::BTreeSet;
+
+/// Free-form skill tags, e.g. "driver" or "food handler"
+pub type Tags = BTreeSet<String>;
+
+/// Parses a comma separated list of tags, ignoring empty entries
+pub fn parse_tags(tags: &str) -> Tags {
+    tags.split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Joins tags back into the comma separated form accepted by `parse_tags`
+pub fn tags_to_string(tags: &Tags) -> String {
+    tags.iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
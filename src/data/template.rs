@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use crate::data::Job;
+
+/// A named set of jobs to start a chart from, e.g. for a camp or a holiday dinner
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct JobTemplate {
+    name: String,
+    #[serde(default)]
+    jobs: Vec<Job>,
+}
+
+impl JobTemplate {
+    pub fn new<N: Into<String>>(name: N, jobs: Vec<Job>) -> Self {
+        Self {
+            name: name.into(),
+            jobs,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn jobs(&self) -> &[Job] {
+        &self.jobs
+    }
+
+    /// Replaces all the jobs with the template's
+    pub fn replace(&self, jobs: &mut Vec<Job>) {
+        *jobs = self.jobs.clone();
+    }
+
+    /// Adds the template's jobs after the existing ones, skipping any with a name that's already used
+    pub fn extend(&self, jobs: &mut Vec<Job>) {
+        let key = |job: &Job| job.name().trim().to_lowercase();
+        let added = self
+            .jobs
+            .iter()
+            .filter(|job| !jobs.iter().any(|j| key(j) == key(job)))
+            .cloned()
+            .collect::<Vec<_>>();
+        jobs.extend(added);
+    }
+}
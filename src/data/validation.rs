@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+use crate::data::{Abilities, Job, Person, Recurrence};
+
+/// Something that has to be fixed before people or jobs can be saved
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Invalid {
+    EmptyName,
+    DuplicateName,
+    UnknownAbility,
+    NoSlots,
+    NoDays,
+}
+
+impl Invalid {
+    pub fn message(self) -> &'static str {
+        match self {
+            Invalid::EmptyName => "a name is needed",
+            Invalid::DuplicateName => "the name is already used",
+            Invalid::UnknownAbility => "the age group no longer exists",
+            Invalid::NoSlots => "at least one person is needed",
+            Invalid::NoDays => "it has to be every 1 day or more",
+        }
+    }
+}
+
+impl Display for Invalid {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", self.message())
+    }
+}
+
+/// The problems with one person, if any
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PersonErrors {
+    pub name: Option<Invalid>,
+    pub ability: Option<Invalid>,
+}
+
+impl PersonErrors {
+    pub fn is_valid(&self) -> bool {
+        self.name.is_none() && self.ability.is_none()
+    }
+}
+
+/// The problems with one job, if any
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct JobErrors {
+    pub name: Option<Invalid>,
+    pub slots: Option<Invalid>,
+    pub recurrence: Option<Invalid>,
+}
+
+impl JobErrors {
+    pub fn is_valid(&self) -> bool {
+        self.name.is_none() && self.slots.is_none() && self.recurrence.is_none()
+    }
+}
+
+/// Names are compared trimmed and ignoring case, so "Jane" and "jane " are the same person
+fn name_key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Checks each name is set and only used once, in the same order as the names
+fn validate_names<'a, I: Iterator<Item = &'a str> + Clone>(names: I) -> Vec<Option<Invalid>> {
+    let mut counts = HashMap::<String, usize>::new();
+    for name in names.clone() {
+        *counts.entry(name_key(name)).or_default() += 1;
+    }
+
+    names
+        .map(|name| {
+            let key = name_key(name);
+            if key.is_empty() {
+                Some(Invalid::EmptyName)
+            } else if counts[&key] > 1 {
+                Some(Invalid::DuplicateName)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// The problems with each person, in the same order as `people`
+pub fn validate_people(people: &[Person], abilities: &Abilities) -> Vec<PersonErrors> {
+    validate_names(people.iter().map(Person::name))
+        .into_iter()
+        .zip(people)
+        .map(|(name, person)| PersonErrors {
+            name,
+            ability: if abilities.contains(person.ability()) {
+                None
+            } else {
+                Some(Invalid::UnknownAbility)
+            },
+        })
+        .collect()
+}
+
+/// The problems with each job, in the same order as `jobs`
+pub fn validate_jobs(jobs: &[Job]) -> Vec<JobErrors> {
+    validate_names(jobs.iter().map(Job::name))
+        .into_iter()
+        .zip(jobs)
+        .map(|(name, job)| JobErrors {
+            name,
+            slots: if job.people().is_empty() {
+                Some(Invalid::NoSlots)
+            } else {
+                None
+            },
+            recurrence: if *job.recurrence() == Recurrence::EveryNDays(0) {
+                Some(Invalid::NoDays)
+            } else {
+                None
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Ability;
+
+    #[test]
+    fn people_need_a_name_of_their_own() {
+        let adult = || Ability::new("Adult");
+        let people = vec![
+            Person::new("Jane", adult()),
+            Person::new(" jane ", adult()),
+            Person::new("  ", adult()),
+            Person::new("Joe", adult()),
+        ];
+        let names = validate_people(&people, &Abilities::default())
+            .into_iter()
+            .map(|e| e.name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                Some(Invalid::DuplicateName),
+                Some(Invalid::DuplicateName),
+                Some(Invalid::EmptyName),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn people_need_a_known_ability() {
+        let people = vec![Person::new("Jane", Ability::new("Wizard"))];
+        let errors = validate_people(&people, &Abilities::default());
+        assert_eq!(errors[0].ability, Some(Invalid::UnknownAbility));
+        assert!(!errors[0].is_valid());
+    }
+
+    #[test]
+    fn jobs_need_slots_and_days() {
+        let mut never = Job::new("Laundry", vec![Ability::new("Adult")]);
+        never.set_recurrence(Recurrence::EveryNDays(0));
+        let jobs = vec![
+            Job::new("Dinner", vec![Ability::new("Adult")]),
+            Job::new("Dishes", Vec::<Ability>::new()),
+            never,
+        ];
+        let errors = validate_jobs(&jobs);
+
+        assert!(errors[0].is_valid());
+        assert_eq!(errors[1].slots, Some(Invalid::NoSlots));
+        assert_eq!(errors[2].recurrence, Some(Invalid::NoDays));
+        assert!(!errors[2].is_valid());
+    }
+}
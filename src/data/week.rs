@@ -1,24 +1,679 @@
-use crate::data::{Day, Job};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt::{self, Display, Formatter};
 
-#[derive(Clone, Debug)]
+use crate::data::{Day, Job, Person, Slot};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Week {
     week: Vec<Day>,
+    /// trades made after the week was calculated, in the order they were made
+    #[serde(default)]
+    swaps: Vec<(Assignment, Assignment)>,
+    /// people put in to make up for jobs they missed, before any trades
+    #[serde(default)]
+    makeups: Vec<(Assignment, Person)>,
+    /// the assignments that were checked off as done
+    #[serde(default)]
+    done: BTreeSet<Assignment>,
+}
+
+/// One person's place in the week, indexes of the day, the job on that day and the slot on the job
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Assignment {
+    pub day: usize,
+    pub job: usize,
+    pub slot: usize,
+}
+
+/// A job someone didn't do, that they owe another time
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Makeup {
+    pub person: Person,
+    pub job: String,
+}
+
+/// Why two assignments could not be swapped
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SwapError {
+    NoSuchAssignment(Assignment),
+    NotQualified {
+        person: String,
+        job: String,
+    },
+    /// The person would have the same job twice on one day
+    AlreadyOnJob {
+        person: String,
+        job: String,
+    },
+}
+
+impl Display for SwapError {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            SwapError::NoSuchAssignment(a) => write!(
+                fmt,
+                "no assignment for slot {} of job {} on day {}",
+                a.slot, a.job, a.day
+            ),
+            SwapError::NotQualified { person, job } => {
+                write!(fmt, "{} can't do the {} slot", person, job)
+            }
+            SwapError::AlreadyOnJob { person, job } => {
+                write!(fmt, "{} already has the {} job that day", person, job)
+            }
+        }
+    }
 }
 
 impl Week {
     pub fn new(week: Vec<Day>) -> Self {
-        Self { week }
+        Self {
+            week,
+            swaps: Vec::new(),
+            makeups: Vec::new(),
+            done: BTreeSet::new(),
+        }
+    }
+
+    /// The first day of the week, None if it has no days
+    pub fn start(&self) -> Option<NaiveDate> {
+        self.week.first().map(Day::date)
+    }
+
+    /// The last day of the week, None if it has no days
+    pub fn end(&self) -> Option<NaiveDate> {
+        self.week.last().map(Day::date)
     }
 
     pub fn num_jobs(&self) -> usize {
-        self.week[0].jobs().len()
+        self.jobs().count()
     }
 
     pub fn days(&self) -> &[Day] {
         &self.week
     }
 
+    /// Every job done on any day of the week
+    ///
+    /// Days only have the jobs that happen on them, so this merges them all keeping the order
+    ///  the jobs were listed in.
     pub fn jobs(&self) -> impl Iterator<Item = &Job> {
-        self.week[0].jobs().iter().map(|(job, _)| job)
+        let mut jobs = Vec::<&Job>::new();
+        for day in self.week.iter() {
+            let mut insert_at = 0;
+            for (job, _) in day.jobs().iter() {
+                match jobs.iter().position(|j| j.name() == job.name()) {
+                    Some(idx) => insert_at = idx + 1,
+                    None => {
+                        jobs.insert(insert_at, job);
+                        insert_at += 1;
+                    }
+                }
+            }
+        }
+
+        jobs.into_iter()
+    }
+
+    /// The week as rows of text cells
+    ///
+    /// The first row is the header with the day names, every other row is a job followed by the
+    ///  people doing it each day, empty if the job isn't done that day.
+    pub fn to_rows(&self) -> Vec<Vec<String>> {
+        let header = std::iter::once("Job".to_string())
+            .chain(self.week.iter().map(|d| d.name().to_string()))
+            .collect();
+
+        let job_row = |job: &Job| {
+            std::iter::once(job.to_string())
+                .chain(self.week.iter().map(|d| {
+                    d.get_job_people(job)
+                        .map(|people| {
+                            people
+                                .iter()
+                                .map(|p| p.to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        })
+                        .unwrap_or_default()
+                }))
+                .collect()
+        };
+
+        std::iter::once(header)
+            .chain(self.jobs().map(job_row))
+            .collect()
+    }
+
+    pub fn swaps(&self) -> &[(Assignment, Assignment)] {
+        &self.swaps
+    }
+
+    /// true if the assignment was traded after the week was calculated
+    pub fn is_swapped(&self, assignment: Assignment) -> bool {
+        self.swaps
+            .iter()
+            .any(|(a, b)| *a == assignment || *b == assignment)
+    }
+
+    pub fn makeups(&self) -> &[(Assignment, Person)] {
+        &self.makeups
+    }
+
+    /// true if the slot was given to someone to make up for a job they missed
+    pub fn is_makeup(&self, assignment: Assignment) -> bool {
+        self.makeups.iter().any(|(a, _)| *a == assignment)
+    }
+
+    pub fn done(&self) -> &BTreeSet<Assignment> {
+        &self.done
+    }
+
+    pub fn is_done(&self, assignment: Assignment) -> bool {
+        self.done.contains(&assignment)
+    }
+
+    /// Checks the assignment off, or unchecks it, returns whether it's now done
+    pub fn toggle_done(&mut self, assignment: Assignment) -> Result<bool, SwapError> {
+        self.get(assignment)
+            .ok_or(SwapError::NoSuchAssignment(assignment))?;
+        if self.done.remove(&assignment) {
+            Ok(false)
+        } else {
+            self.done.insert(assignment);
+            Ok(true)
+        }
+    }
+
+    /// Checks off the same jobs as in another calculation of the week, where the same person
+    ///  still has them
+    pub fn keep_done(&mut self, other: &Week) {
+        for assignment in other.done.iter() {
+            let (job, _, person) = match other.get(*assignment) {
+                Some(found) => found,
+                None => continue,
+            };
+            let date = other.week[assignment.day].date();
+            let day = match self.week.iter().position(|d| d.date() == date) {
+                Some(day) => day,
+                None => continue,
+            };
+            let kept = self.week[day].find_job(job).and_then(|job| {
+                let (_, people) = &self.week[day].jobs()[job];
+                people
+                    .iter()
+                    .position(|p| p.name() == person.name())
+                    .map(|slot| Assignment { day, job, slot })
+            });
+            if let Some(kept) = kept {
+                self.done.insert(kept);
+            }
+        }
+    }
+
+    /// The jobs that weren't checked off, for anyone who's still on the roster
+    ///
+    /// Only days with something checked off count, a day nobody checked off is taken as one
+    ///  check-offs weren't used on rather than one where every job was missed.
+    pub fn missed(&self, people: &[Person]) -> Vec<Makeup> {
+        let mut missed = Vec::new();
+        for (day_idx, day) in self.week.iter().enumerate() {
+            if !self.done.iter().any(|a| a.day == day_idx) {
+                continue;
+            }
+
+            for (job_idx, (job, workers)) in day.jobs().iter().enumerate() {
+                for (slot, worker) in workers.iter().enumerate() {
+                    let assignment = Assignment {
+                        day: day_idx,
+                        job: job_idx,
+                        slot,
+                    };
+                    if self.is_done(assignment) || worker.is_placeholder() {
+                        continue;
+                    }
+                    if let Some(person) = people.iter().find(|p| p.name() == worker.name()) {
+                        missed.push(Makeup {
+                            person: person.clone(),
+                            job: job.name().to_string(),
+                        });
+                    }
+                }
+            }
+        }
+        missed
+    }
+
+    /// Gives each person a job they owe on a day from `from` on, in place of whoever had it
+    ///
+    /// The same job is used if it's done on one of those days, otherwise the first job they can
+    ///  do. Nobody gets the same job twice on a day. Returns the makeups there wasn't a place for.
+    pub fn add_makeups(&mut self, owed: &[Makeup], from: NaiveDate) -> Vec<Makeup> {
+        let mut unplaced = Vec::new();
+        for makeup in owed {
+            let place = self
+                .makeup_place(makeup, from, true)
+                .or_else(|| self.makeup_place(makeup, from, false));
+            match place {
+                Some(assignment) => {
+                    self.set(assignment, makeup.person.clone());
+                    self.makeups.push((assignment, makeup.person.clone()));
+                }
+                None => unplaced.push(makeup.clone()),
+            }
+        }
+        unplaced
+    }
+
+    /// Puts the makeups back in the same places, e.g. in a shared chart calculated again
+    pub fn replay_makeups(&mut self, makeups: &[(Assignment, Person)]) {
+        for (assignment, person) in makeups {
+            if self.set(*assignment, person.clone()).is_some() {
+                self.makeups.push((*assignment, person.clone()));
+            }
+        }
+    }
+
+    /// The first slot the person could make up the job in
+    fn makeup_place(&self, makeup: &Makeup, from: NaiveDate, same_job: bool) -> Option<Assignment> {
+        for (day_idx, day) in self.week.iter().enumerate() {
+            if day.date() < from {
+                continue;
+            }
+
+            for (job_idx, (job, workers)) in day.jobs().iter().enumerate() {
+                if same_job && job.name() != makeup.job {
+                    continue;
+                }
+                if workers.iter().any(|w| w.name() == makeup.person.name()) {
+                    continue;
+                }
+
+                for (slot_idx, slot) in job.people().iter().enumerate() {
+                    let assignment = Assignment {
+                        day: day_idx,
+                        job: job_idx,
+                        slot: slot_idx,
+                    };
+                    if slot.accepts(&makeup.person) && !self.is_makeup(assignment) {
+                        return Some(assignment);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// The slot and the person filling it
+    pub fn get(&self, assignment: Assignment) -> Option<(&Job, &Slot, &Person)> {
+        let (job, people) = self.week.get(assignment.day)?.jobs().get(assignment.job)?;
+        let slot = job.people().get(assignment.slot)?;
+        let person = people.get(assignment.slot)?;
+        Some((job, slot, person))
+    }
+
+    /// Trades the people in the two assignments, each has to be able to fill the other's slot
+    pub fn swap(&mut self, a: Assignment, b: Assignment) -> Result<(), SwapError> {
+        let (a_job, a_slot, a_person) = self.get(a).ok_or(SwapError::NoSuchAssignment(a))?;
+        let (b_job, b_slot, b_person) = self.get(b).ok_or(SwapError::NoSuchAssignment(b))?;
+
+        if !b_slot.accepts(a_person) {
+            return Err(SwapError::NotQualified {
+                person: a_person.name().to_string(),
+                job: b_job.name().to_string(),
+            });
+        }
+        if !a_slot.accepts(b_person) {
+            return Err(SwapError::NotQualified {
+                person: b_person.name().to_string(),
+                job: a_job.name().to_string(),
+            });
+        }
+
+        if (a.day, a.job) != (b.day, b.job) {
+            if self.has_other_slot(b, a_person) {
+                return Err(SwapError::AlreadyOnJob {
+                    person: a_person.name().to_string(),
+                    job: b_job.name().to_string(),
+                });
+            }
+            if self.has_other_slot(a, b_person) {
+                return Err(SwapError::AlreadyOnJob {
+                    person: b_person.name().to_string(),
+                    job: a_job.name().to_string(),
+                });
+            }
+        }
+
+        self.exchange(a, b)?;
+        self.swaps.push((a, b));
+
+        // a check off goes with the person who did it
+        let (a_done, b_done) = (self.done.remove(&a), self.done.remove(&b));
+        if a_done {
+            self.done.insert(b);
+        }
+        if b_done {
+            self.done.insert(a);
+        }
+        Ok(())
+    }
+
+    /// Makes the same trades as in another calculation of the week, where they still fit
+    ///
+    /// Each trade is found by the date, job and names of the two people in it, so it's kept when
+    ///  the same people still have those jobs. Returns how many trades were dropped.
+    pub fn replay_swaps(&mut self, other: &Week) -> usize {
+        // the other week as it was before any trades
+        let mut before = other.clone();
+        for (a, b) in other.swaps.iter().rev() {
+            before.exchange(*a, *b).ok();
+        }
+
+        let mut dropped = 0;
+        for (a, b) in other.swaps.iter() {
+            let traded = match (self.find_same(&before, *a), self.find_same(&before, *b)) {
+                (Some(new_a), Some(new_b)) => self.swap(new_a, new_b).is_ok(),
+                _ => false,
+            };
+            if !traded {
+                dropped += 1;
+            }
+            before.exchange(*a, *b).ok();
+        }
+        dropped
+    }
+
+    /// The assignment with the same date, job and person as one in another week
+    fn find_same(&self, other: &Week, assignment: Assignment) -> Option<Assignment> {
+        let (job, _, person) = other.get(assignment)?;
+        let date = other.week.get(assignment.day)?.date();
+        let day = self.week.iter().position(|d| d.date() == date)?;
+        let job = self.week[day].find_job(job)?;
+        let slot = self.week[day].jobs()[job]
+            .1
+            .iter()
+            .position(|p| p.name() == person.name())?;
+        Some(Assignment { day, job, slot })
+    }
+
+    /// true if the person has another slot on the assignment's job that day
+    fn has_other_slot(&self, assignment: Assignment, person: &Person) -> bool {
+        self.week
+            .get(assignment.day)
+            .and_then(|d| d.jobs().get(assignment.job))
+            .is_some_and(|(_, people)| {
+                people
+                    .iter()
+                    .enumerate()
+                    .any(|(slot, p)| slot != assignment.slot && p.name() == person.name())
+            })
+    }
+
+    /// Trades the person in `from` onto a job on another day or another job, with the first person
+    ///  there who they can trade with
+    pub fn move_to(
+        &mut self,
+        from: Assignment,
+        day: usize,
+        job: usize,
+    ) -> Result<Assignment, SwapError> {
+        if from.day == day && from.job == job {
+            return Ok(from);
+        }
+
+        let num_slots = self
+            .week
+            .get(day)
+            .and_then(|d| d.jobs().get(job))
+            .map_or(0, |(job, _)| job.people().len());
+        let mut error = SwapError::NoSuchAssignment(Assignment { day, job, slot: 0 });
+        for slot in 0..num_slots {
+            let to = Assignment { day, job, slot };
+            match self.swap(from, to) {
+                Ok(()) => return Ok(to),
+                Err(err) if slot == 0 => error = err,
+                Err(_) => (),
+            }
+        }
+
+        Err(error)
+    }
+
+    /// Trades the people in the two assignments without any checks
+    fn exchange(&mut self, a: Assignment, b: Assignment) -> Result<(), SwapError> {
+        let a_person = self
+            .get(a)
+            .map(|(_, _, p)| p.clone())
+            .ok_or(SwapError::NoSuchAssignment(a))?;
+        let b_person = self
+            .set(b, a_person)
+            .ok_or(SwapError::NoSuchAssignment(b))?;
+        self.set(a, b_person)
+            .ok_or(SwapError::NoSuchAssignment(a))?;
+        Ok(())
+    }
+
+    /// Puts the person in the assignment, returning who was there before
+    fn set(&mut self, assignment: Assignment, person: Person) -> Option<Person> {
+        let (_, people) = self
+            .week
+            .get_mut(assignment.day)?
+            .jobs_mut()
+            .get_mut(assignment.job)?;
+        let current = people.get_mut(assignment.slot)?;
+        Some(std::mem::replace(current, person))
+    }
+}
+
+/// Renders the week as a plain text table, one row per job and a column for each day
+impl Display for Week {
+    fn fmt(&self, fmt: &mut Formatter) -> Result<(), fmt::Error> {
+        let rows = self.to_rows();
+        let mut widths = vec![0_usize; rows.first().map_or(0, Vec::len)];
+        for row in rows.iter() {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        for (i, row) in rows.iter().enumerate() {
+            let cells = row
+                .iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>();
+            writeln!(fmt, "{}", cells.join(" | ").trim_end())?;
+
+            if i == 0 {
+                let rule = widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>();
+                writeln!(fmt, "{}", rule.join("-+-"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Abilities, Ability, Settings};
+
+    fn people(without: &str) -> Vec<Person> {
+        let adult = || Ability::new("Adult");
+        let teen = || Ability::new("Teen");
+        vec![
+            Person::new("Ann", adult()),
+            Person::new("Bob", adult()),
+            Person::new("Cat", adult()),
+            Person::new("Tim", teen()),
+            Person::new("Tia", teen()),
+        ]
+        .into_iter()
+        .filter(|p| p.name() != without)
+        .collect()
+    }
+
+    fn settings() -> Settings {
+        Settings::new(NaiveDate::from_ymd_opt(2020, 7, 1).unwrap(), 3)
+    }
+
+    /// Dinner is Ann and Bob, then Bob and Cat, then Cat and Ann, dishes are Tim, Tia, Tim
+    fn week_without(without: &str) -> Week {
+        let jobs = vec![
+            Job::new("Dinner", vec![Ability::new("Adult"); 2]),
+            Job::new("Dishes", vec![Ability::new("Teen")]),
+        ];
+        crate::calculate(&settings(), &Abilities::default(), jobs, people(without))
+    }
+
+    fn week() -> Week {
+        week_without("")
+    }
+
+    fn at(day: usize, job: usize, slot: usize) -> Assignment {
+        Assignment { day, job, slot }
+    }
+
+    fn name(week: &Week, assignment: Assignment) -> &str {
+        week.get(assignment).unwrap().2.name()
+    }
+
+    #[test]
+    fn swap_trades_the_people() {
+        let mut week = week();
+        week.swap(at(0, 0, 0), at(1, 0, 1)).unwrap();
+
+        assert_eq!(name(&week, at(0, 0, 0)), "Cat");
+        assert_eq!(name(&week, at(1, 0, 1)), "Ann");
+        assert!(week.is_swapped(at(0, 0, 0)));
+        assert_eq!(week.swaps(), [(at(0, 0, 0), at(1, 0, 1))]);
+    }
+
+    #[test]
+    fn swap_needs_people_who_can_do_the_job() {
+        let mut week = week();
+        let err = week.swap(at(0, 0, 0), at(0, 1, 0)).unwrap_err();
+
+        assert_eq!(
+            err,
+            SwapError::NotQualified {
+                person: "Ann".to_string(),
+                job: "Dishes".to_string(),
+            }
+        );
+        assert!(week.swaps().is_empty());
+    }
+
+    #[test]
+    fn swap_refuses_the_same_job_twice_on_a_day() {
+        let mut week = week();
+        let err = week.swap(at(0, 0, 0), at(1, 0, 0)).unwrap_err();
+
+        assert_eq!(
+            err,
+            SwapError::AlreadyOnJob {
+                person: "Bob".to_string(),
+                job: "Dinner".to_string(),
+            }
+        );
+        assert_eq!(name(&week, at(0, 0, 0)), "Ann");
+    }
+
+    #[test]
+    fn swap_within_a_job() {
+        let mut week = week();
+        week.swap(at(0, 0, 0), at(0, 0, 1)).unwrap();
+        assert_eq!(name(&week, at(0, 0, 0)), "Bob");
+    }
+
+    #[test]
+    fn move_to_trades_with_the_first_person_it_can() {
+        let mut week = week();
+        // Bob is first on the day but already has the dinner Ann would leave
+        assert_eq!(week.move_to(at(0, 0, 0), 1, 0), Ok(at(1, 0, 1)));
+        assert_eq!(name(&week, at(1, 0, 1)), "Ann");
+        assert_eq!(name(&week, at(0, 0, 0)), "Cat");
+    }
+
+    #[test]
+    fn check_offs_go_with_the_person() {
+        let mut week = week();
+        assert_eq!(week.toggle_done(at(0, 0, 0)), Ok(true));
+        week.swap(at(0, 0, 0), at(1, 0, 1)).unwrap();
+
+        assert!(!week.is_done(at(0, 0, 0)));
+        assert!(week.is_done(at(1, 0, 1)));
+    }
+
+    #[test]
+    fn missed_counts_days_with_check_offs() {
+        let mut week = week();
+        week.toggle_done(at(0, 0, 0)).unwrap();
+
+        let missed = week.missed(&people(""));
+        let missed = missed
+            .iter()
+            .map(|m| (m.person.name(), m.job.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(missed, [("Bob", "Dinner"), ("Tim", "Dishes")]);
+
+        // Bob's gone, so he doesn't owe his dinner
+        assert_eq!(week.missed(&people("Bob")).len(), 1);
+    }
+
+    #[test]
+    fn missed_leaves_out_days_nothing_was_checked_off() {
+        assert!(week().missed(&people("")).is_empty());
+    }
+
+    #[test]
+    fn add_makeups_from_the_date() {
+        let mut week = week();
+        let makeup = |person: &str, ability: &str, job: &str| Makeup {
+            person: Person::new(person, Ability::new(ability)),
+            job: job.to_string(),
+        };
+        let from = NaiveDate::from_ymd_opt(2020, 7, 2).unwrap();
+
+        let unplaced = week.add_makeups(
+            &[
+                makeup("Ann", "Adult", "Dinner"),
+                // not a job Tim can do, so the first one he can
+                makeup("Tim", "Teen", "Dinner"),
+                makeup("Kid", "Child", "Dinner"),
+            ],
+            from,
+        );
+
+        assert_eq!(name(&week, at(1, 0, 0)), "Ann");
+        assert!(week.is_makeup(at(1, 0, 0)));
+        assert_eq!(name(&week, at(1, 1, 0)), "Tim");
+        assert_eq!(unplaced, [makeup("Kid", "Child", "Dinner")]);
+    }
+
+    #[test]
+    fn replay_swaps_on_the_same_week() {
+        let mut traded = week();
+        traded.swap(at(0, 0, 0), at(1, 0, 1)).unwrap();
+        traded.swap(at(0, 1, 0), at(1, 1, 0)).unwrap();
+
+        let mut again = week();
+        assert_eq!(again.replay_swaps(&traded), 0);
+        assert_eq!(again.to_rows(), traded.to_rows());
+        assert_eq!(again.swaps(), traded.swaps());
+    }
+
+    #[test]
+    fn replay_swaps_drops_those_that_no_longer_fit() {
+        let mut traded = week();
+        traded.swap(at(0, 1, 0), at(1, 1, 0)).unwrap();
+
+        // without Tia the dishes are all Tim's, so there's nobody to trade with
+        let mut again = week_without("Tia");
+        assert_eq!(again.replay_swaps(&traded), 1);
+        assert!(again.swaps().is_empty());
     }
 }
@@ -1,114 +1,208 @@
 #![recursion_limit = "2048"]
 
+//! Kitchen Patrol chart scheduling
+//!
+//! The `data` types and `calculate` are platform independent, the Yew browser app in `web` is
+//!  only built with the `web` feature.
+
 pub mod data;
+#[cfg(feature = "web")]
 pub mod web;
 
-use log::Level;
-use std::iter::*;
-use wasm_bindgen::prelude::*;
-use yew::prelude::*;
+use std::collections::BTreeMap;
+
+use chrono::Weekday;
 
 use self::data::*;
 
 pub fn default_jobs() -> Vec<Job> {
-    let mut jobs = Vec::<Job>::new();
-    jobs.push(Job::new(
-        "Breakfast dishes",
-        vec![Ability::Teen, Ability::Child],
-    ));
-    jobs.push(Job::new(
-        "Lunch preparation",
-        vec![Ability::Adult, Ability::Adult],
-    ));
-    jobs.push(Job::new(
-        "Lunch dishes",
-        vec![Ability::Adult, Ability::Teen],
-    ));
-    jobs.push(Job::new(
-        "Dinner Setting",
-        vec![Ability::Teen, Ability::Child, Ability::Child],
-    ));
-    jobs.push(Job::new(
-        "Dinner shopping and chef",
-        vec![Ability::Adult, Ability::Adult],
-    ));
-    jobs.push(Job::new(
-        "Dinner dishes",
-        vec![Ability::Adult, Ability::Teen],
-    ));
-    jobs.push(Job::new("Late night dishes", vec![Ability::Teen]));
-    jobs.push(Job::new("Cabin cleanup", vec![Ability::Adult]));
-    jobs.push(Job::new("Nag", vec![Ability::Adult]));
-
-    jobs
+    let adult = || Ability::new("Adult");
+    let teen = || Ability::new("Teen");
+    let child = || Ability::new("Child");
+
+    vec![
+        Job::new("Breakfast dishes", vec![teen(), child()]),
+        Job::new("Lunch preparation", vec![adult(), adult()]),
+        Job::new("Lunch dishes", vec![adult(), teen()]),
+        Job::new("Dinner Setting", vec![teen(), child(), child()]),
+        Job::new("Dinner shopping and chef", vec![adult(), adult()]),
+        Job::new("Dinner dishes", vec![adult(), teen()]),
+        Job::new("Late night dishes", vec![teen()]),
+        Job::new("Cabin cleanup", vec![adult()]),
+        Job::new("Nag", vec![adult()]),
+    ]
+}
+
+/// The templates that come with the app, the first is the same as `default_jobs`
+pub fn builtin_templates() -> Vec<JobTemplate> {
+    let adult = || Ability::new("Adult");
+    let teen = || Ability::new("Teen");
+    let child = || Ability::new("Child");
+    let on = |days: &[Weekday]| Recurrence::Weekdays(days.to_vec());
+
+    vec![
+        JobTemplate::new("Family camp", default_jobs()),
+        JobTemplate::new(
+            "Ski cabin",
+            vec![
+                Job::new("Breakfast", vec![adult(), teen()]),
+                Job::new("Pack lunches", vec![adult(), child()]),
+                Job::new("Dinner chef", vec![adult(), adult()]),
+                Job::new("Dinner dishes", vec![teen(), teen(), child()]),
+                Job::new("Shovel the walk", vec![adult(), teen()]),
+                Job::new("Firewood", vec![teen(), child()]),
+                Job::new("Dry the gear", vec![child()]),
+                Job::new("Grocery run", vec![adult()]).with_recurrence(Recurrence::EveryNDays(3)),
+            ],
+        ),
+        JobTemplate::new(
+            "Holiday dinner",
+            vec![
+                Job::new("Roast", vec![adult()]),
+                Job::new("Sides", vec![adult(), teen()]),
+                Job::new("Desserts", vec![adult(), child()]),
+                Job::new("Set the table", vec![teen(), child()]),
+                Job::new("Greet guests", vec![child()]),
+                Job::new("Carving", vec![adult()]),
+                Job::new("Clear the table", vec![teen(), child()]),
+                Job::new("Dishes", vec![adult(), teen(), teen()]),
+            ],
+        ),
+        JobTemplate::new(
+            "Small household chores",
+            vec![
+                Job::new("Dishes", vec![adult(), child()]),
+                Job::new("Feed the pets", vec![child()]),
+                Job::new("Take out the trash", vec![teen()])
+                    .with_recurrence(on(&[Weekday::Mon, Weekday::Thu])),
+                Job::new("Vacuum", vec![teen()]).with_recurrence(Recurrence::EveryNDays(3)),
+                Job::new("Laundry", vec![adult(), teen()]).with_recurrence(on(&[Weekday::Sat])),
+                Job::new("Water the plants", vec![child()])
+                    .with_recurrence(on(&[Weekday::Wed, Weekday::Sun])),
+            ],
+        ),
+    ]
 }
 
 pub fn default_people() -> Vec<Person> {
-    let mut people = Vec::<Person>::new();
-    people.push(Person::new("Grandma", Ability::Adult));
-    people.push(Person::new("Grandpa", Ability::Adult));
-    people.push(Person::new("Mom", Ability::Adult));
-    people.push(Person::new("Dad", Ability::Adult));
-    people.push(Person::new("Aunt Jane", Ability::Adult));
-    people.push(Person::new("Uncle Joe", Ability::Adult));
-    people.push(Person::new("Jackie", Ability::Teen));
-    people.push(Person::new("Jake", Ability::Teen));
-    people.push(Person::new("Jill", Ability::Child));
-    people.push(Person::new("Jeffrey", Ability::Child));
-
-    return people;
+    let adult = || Ability::new("Adult");
+    let teen = || Ability::new("Teen");
+    let child = || Ability::new("Child");
+
+    vec![
+        Person::new("Grandma", adult()),
+        Person::new("Grandpa", adult()),
+        Person::new("Mom", adult()),
+        Person::new("Dad", adult()),
+        Person::new("Aunt Jane", adult()),
+        Person::new("Uncle Joe", adult()),
+        Person::new("Jackie", teen()),
+        Person::new("Jake", teen()),
+        Person::new("Jill", child()),
+        Person::new("Jeffrey", child()),
+    ]
+}
+
+/// Orders the people so whoever did the fewest jobs per week in the past weeks is first in line
+///
+/// The rotations hand out the jobs in this order, so when they don't divide evenly the extra jobs
+///  go to those who did less before. People keep their order otherwise.
+pub fn balance(people: &[Person], past: &[Week]) -> Vec<Person> {
+    let mut fairness = Fairness::across(past, people);
+    fairness.sort_by(Fairness::cmp_per_week);
+    fairness.into_iter().map(|f| f.person().clone()).collect()
 }
 
 pub fn calculate_day_jobs() -> Week {
     let jobs = default_jobs();
     let people = default_people();
-    calculate(5, jobs, people)
+    calculate(&Settings::default(), &Abilities::default(), jobs, people)
 }
 
-pub fn calculate(num_days: usize, jobs: Vec<Job>, people: Vec<Person>) -> Week {
-    let children = people
-        .clone()
-        .into_iter()
-        .filter(|p| p.ability() == Ability::Child)
-        .collect::<Vec<Person>>();
-    let mut children_iter = children.iter().cycle();
+pub fn calculate(
+    settings: &Settings,
+    abilities: &Abilities,
+    jobs: Vec<Job>,
+    people: Vec<Person>,
+) -> Week {
+    schedule(settings, abilities, jobs, people, None)
+}
 
-    let teens = people
-        .clone()
-        .into_iter()
-        .filter(|p| p.ability() == Ability::Teen)
-        .collect::<Vec<Person>>();
-    let mut teens_iter = teens.iter().cycle();
+/// Why each slot of the week `calculate` makes was filled the way it was, in the order they were filled
+pub fn explain(
+    settings: &Settings,
+    abilities: &Abilities,
+    jobs: Vec<Job>,
+    people: Vec<Person>,
+) -> Vec<Explanation> {
+    let mut tracer = Tracer::default();
+    schedule(settings, abilities, jobs, people, Some(&mut tracer));
+    tracer.into_explanations()
+}
 
-    let adults = people
-        .clone()
+fn schedule(
+    settings: &Settings,
+    abilities: &Abilities,
+    jobs: Vec<Job>,
+    people: Vec<Person>,
+    mut tracer: Option<&mut Tracer>,
+) -> Week {
+    // one rotation per ability group, each keeps the order people were entered in
+    let mut groups = BTreeMap::<Ability, Vec<Person>>::new();
+    for person in people {
+        groups
+            .entry(person.ability().clone())
+            .or_default()
+            .push(person);
+    }
+    let mut rotations = groups
         .into_iter()
-        .filter(|p| p.ability() == Ability::Adult)
-        .collect::<Vec<Person>>();
-    let mut adults_iter = adults.iter().cycle();
-
-    // make sure we have a good balance of jobs across adults, we nee the count of adult jobs
-    let adult_job_count = jobs.iter().fold(0_usize, |count, j| {
-        j.people().iter().filter(|a| **a == Ability::Adult).count() + count
-    });
-
-    let mut days = Vec::with_capacity(num_days);
-    for i in 0..num_days {
-        let day = Day::new(
-            format!("day_{}", i),
-            jobs.clone(),
-            &mut children_iter,
-            &mut teens_iter,
-            &mut adults_iter,
-        );
+        .map(|(ability, people)| (ability, Rotation::new(people)))
+        .collect::<BTreeMap<_, _>>();
+
+    let primary = abilities.first();
+    let mut days = Vec::with_capacity(settings.num_days());
+    for (i, date) in settings.dates().enumerate() {
+        let mut day_jobs = jobs
+            .iter()
+            .filter(|j| j.recurrence().occurs(i, date))
+            .cloned()
+            .collect::<Vec<_>>();
+        if let Some(day_override) = settings.day_override(date) {
+            day_jobs = day_override.apply(day_jobs);
+        }
+
+        // make sure we have a good balance of jobs across the first group (adults), we need the count of their jobs
+        let primary_job_count = day_jobs.iter().fold(0_usize, |count, j| {
+            j.people()
+                .iter()
+                .filter(|s| Some(s.ability()) == primary)
+                .count()
+                + count
+        });
+
+        let name = date.format("%a %-m/%-d").to_string();
+        if let Some(tracer) = tracer.as_deref_mut() {
+            tracer.start_day(i);
+        }
+        let day = Day::traced(name, date, day_jobs, &mut rotations, tracer.as_deref_mut());
 
         // force an additional rotation to offset Dinner duty
         //   we need to make sure we balance the rotation of major adult jobs
-        if (adult_job_count + 1) == adults.len() {
-            adults_iter.next();
-            adults_iter.next();
-        } else {
-            adults_iter.next();
+        if let Some((ability, rotation)) =
+            primary.and_then(|a| rotations.get_mut(a).map(|r| (a, r)))
+        {
+            let places = if (primary_job_count + 1) == rotation.len() {
+                2
+            } else {
+                1
+            };
+            for _ in 0..places {
+                rotation.skip();
+            }
+            if let Some(tracer) = tracer.as_deref_mut() {
+                tracer.advanced(ability, places);
+            }
         }
 
         days.push(day);
@@ -117,13 +211,127 @@ pub fn calculate(num_days: usize, jobs: Vec<Job>, people: Vec<Person>) -> Week {
     Week::new(days)
 }
 
-#[wasm_bindgen(start)]
-pub fn start() -> Result<(), JsValue> {
-    console_log::init_with_level(Level::Debug).expect("failed to initialize logger");
+#[cfg(feature = "web")]
+#[wasm_bindgen::prelude::wasm_bindgen(start)]
+pub fn start() -> Result<(), wasm_bindgen::JsValue> {
+    console_log::init_with_level(log::Level::Debug).expect("failed to initialize logger");
     yew::initialize();
 
-    App::<crate::web::RootModel>::new().mount_to_body_with_props(());
+    yew::App::<crate::web::RootModel>::new().mount_to_body_with_props(());
     yew::run_loop();
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn adults(names: &[&str]) -> Vec<Person> {
+        names
+            .iter()
+            .map(|n| Person::new(*n, Ability::new("Adult")))
+            .collect()
+    }
+
+    /// The names on the first job of each day
+    fn first_job(week: &Week) -> Vec<Vec<String>> {
+        week.days()
+            .iter()
+            .map(|d| d.jobs()[0].1.iter().map(|p| p.name().to_string()).collect())
+            .collect()
+    }
+
+    fn settings(num_days: usize) -> Settings {
+        Settings::new(NaiveDate::from_ymd_opt(2020, 7, 1).unwrap(), num_days)
+    }
+
+    #[test]
+    fn advances_two_places_when_one_adult_is_left_over() {
+        let jobs = vec![Job::new("Dinner", vec![Ability::new("Adult"); 2])];
+        let week = calculate(
+            &settings(3),
+            &Abilities::default(),
+            jobs,
+            adults(&["Ann", "Bob", "Cat"]),
+        );
+
+        // without the extra place Ann and Bob would have dinner every day
+        assert_eq!(
+            first_job(&week),
+            [["Ann", "Bob"], ["Bob", "Cat"], ["Cat", "Ann"]]
+        );
+    }
+
+    #[test]
+    fn advances_one_place_otherwise() {
+        let jobs = vec![Job::new("Dinner", vec![Ability::new("Adult"); 2])];
+        let week = calculate(
+            &settings(2),
+            &Abilities::default(),
+            jobs,
+            adults(&["Ann", "Bob", "Cat", "Dan"]),
+        );
+
+        assert_eq!(first_job(&week), [["Ann", "Bob"], ["Dan", "Ann"]]);
+    }
+
+    #[test]
+    fn unfilled_slots_get_a_placeholder() {
+        let jobs = vec![Job::new("Dishes", vec![Ability::new("Teen")])];
+        let week = calculate(&settings(1), &Abilities::default(), jobs, adults(&["Ann"]));
+
+        let (_, _, person) = week
+            .get(Assignment {
+                day: 0,
+                job: 0,
+                slot: 0,
+            })
+            .unwrap();
+        assert!(person.is_placeholder());
+    }
+
+    #[test]
+    fn balance_puts_whoever_did_least_first() {
+        let jobs = vec![Job::new("Dinner", vec![Ability::new("Adult")])];
+        let past = calculate(
+            &settings(2),
+            &Abilities::default(),
+            jobs,
+            adults(&["Ann", "Bob", "Cat"]),
+        );
+
+        let balanced = balance(&adults(&["Ann", "Bob", "Cat"]), &[past]);
+        let names = balanced.iter().map(Person::name).collect::<Vec<_>>();
+        // Bob was skipped over and had no jobs
+        assert_eq!(names, ["Bob", "Ann", "Cat"]);
+    }
+
+    #[test]
+    fn explains_every_slot_as_calculated() {
+        let week = calculate_day_jobs();
+        let explanations = explain(
+            &Settings::default(),
+            &Abilities::default(),
+            default_jobs(),
+            default_people(),
+        );
+
+        let slots = week
+            .days()
+            .iter()
+            .flat_map(|d| d.jobs())
+            .map(|(_, people)| people.len())
+            .sum::<usize>();
+        assert_eq!(explanations.len(), slots);
+        for explanation in explanations.iter() {
+            let (_, _, person) = week.get(explanation.assignment).unwrap();
+            assert_eq!(
+                explanation.chosen().map(|c| c.name.as_str()),
+                Some(person.name())
+            );
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use yew::callback::Callback;
+use yew::format::Json;
+use yew::prelude::*;
+use yew::services::{storage::Area, StorageService};
+
+use crate::data::*;
+use crate::web::history;
+use crate::web::i18n::{self, t, tf};
+use crate::web::remote;
+use crate::web::workspace;
+use crate::web::Id;
+
+pub(crate) const ABILITIES_KEY: &str = "abilities_v1";
+
+pub enum AbilitiesMsg {
+    NameInput(String),
+    AddAbility,
+    MoveUp(Id),
+    DeleteAbility(Id),
+    SaveAbilities,
+}
+
+/// Editor for the list of ability (age) groups
+pub struct AbilitiesModel {
+    inc: usize,
+    abilities: Abilities,
+    new_name: String,
+    history_version: usize,
+    on_save: Option<Callback<usize>>,
+    link: ComponentLink<Self>,
+}
+
+#[derive(Clone, Default, PartialEq, Properties)]
+pub struct AbilitiesProps {
+    /// Changes when an undo or redo replaced what's stored
+    pub history_version: usize,
+    pub on_save: Option<Callback<usize>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AbilitiesStore {
+    pub inc: usize,
+    pub abilities: Abilities,
+}
+
+impl AbilitiesStore {
+    pub fn restore(local_store: &mut StorageService) -> Option<Self> {
+        let from_store = local_store.restore(&workspace::key(ABILITIES_KEY));
+        match from_store {
+            Json(Ok(abilities)) => Some(abilities),
+            Json(Err(err)) => {
+                error!("could not load from local store: {}", err);
+                None
+            }
+        }
+    }
+
+    pub fn store(&mut self, local_store: &mut StorageService) {
+        self.inc += 1;
+        debug!("saving abilities: {}", self.inc);
+        local_store.store(&workspace::key(ABILITIES_KEY), Json(self as &Self));
+        remote::push("abilities");
+    }
+
+    /// The stored version and groups, or the default groups if none have been saved
+    pub fn restore_or_default(local_store: &mut StorageService) -> (usize, Abilities) {
+        Self::restore(local_store)
+            .map(|a| (a.inc, a.abilities))
+            .unwrap_or_default()
+    }
+}
+
+impl Component for AbilitiesModel {
+    type Message = AbilitiesMsg;
+    type Properties = AbilitiesProps;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        debug!("creating AbilitiesModel");
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        let (inc, abilities) = AbilitiesStore::restore_or_default(&mut local_store);
+
+        Self {
+            inc,
+            abilities,
+            new_name: String::new(),
+            history_version: props.history_version,
+            on_save: props.on_save,
+            link,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            AbilitiesMsg::NameInput(name) => {
+                self.new_name = name;
+                false
+            }
+            AbilitiesMsg::AddAbility => {
+                let name = self.new_name.trim();
+                if name.is_empty() {
+                    return false;
+                }
+
+                debug!("adding ability: {}", name);
+                if self.abilities.push(Ability::new(name)) {
+                    self.new_name.clear();
+                }
+                true
+            }
+            AbilitiesMsg::MoveUp(id) => self.abilities.move_up(id),
+            AbilitiesMsg::DeleteAbility(id) => {
+                let ability = self.abilities.remove(id);
+                debug!("deleted {:?}", ability);
+                ability.is_some()
+            }
+            AbilitiesMsg::SaveAbilities => {
+                debug!("saving AbilitiesModel");
+                let mut local_store =
+                    StorageService::new(Area::Local).expect("failed to get storage");
+                let mut abilities = AbilitiesStore {
+                    inc: self.inc,
+                    abilities: self.abilities.clone(),
+                };
+                history::tracked(
+                    &mut local_store,
+                    t("Save age groups").to_string(),
+                    ABILITIES_KEY,
+                    |local_store| abilities.store(local_store),
+                );
+                self.inc = abilities.inc;
+
+                if let Some(e) = self.on_save.as_ref() {
+                    e.emit(self.inc)
+                }
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.history_version == props.history_version {
+            return false;
+        }
+
+        debug!("reloading AbilitiesModel");
+        self.history_version = props.history_version;
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        let (inc, abilities) = AbilitiesStore::restore_or_default(&mut local_store);
+        self.inc = inc;
+        self.abilities = abilities;
+
+        if let Some(e) = self.on_save.as_ref() {
+            e.emit(self.inc)
+        }
+        true
+    }
+
+    fn view(&self) -> Html {
+        let ability_row = |id: Id, ability: &Ability| {
+            let name = i18n::ability_name(ability);
+            let move_up = tf("Move {} up", &[&name]);
+            let delete = tf("Delete {}", &[&name]);
+            html! {
+                <tr>
+                    <td>{ &name }</td>
+                    <td class="edit_delete">
+                        <button type="button" class="icon" aria-label=move_up.clone() title=move_up onclick=self.link.callback(move |_| AbilitiesMsg::MoveUp(id))>
+                            <i class=("fa", "fa-arrow-up", "fa-fw") aria-hidden="true"></i>
+                        </button>
+                        <button type="button" class="icon" aria-label=delete.clone() title=delete onclick=self.link.callback(move |_| AbilitiesMsg::DeleteAbility(id))>
+                            <i class=("fa", "fa-trash", "fa-fw") aria-hidden="true"></i>
+                        </button>
+                    </td>
+                </tr>
+            }
+        };
+
+        html! {
+            <>
+                <h2>{ t("Age groups") }</h2>
+                <table>
+                    <thead>
+                        <tr><th>{ t("Group") }</th><th>{" "}</th></tr>
+                    </thead>
+                    <tbody>
+                        { for self.abilities.iter().enumerate().map(|(i, a)| ability_row(i, a)) }
+                    </tbody>
+                    <tfoot>
+                        <tr><td colspan="2">
+                            <input type="text" placeholder=t("New group") value={&self.new_name} oninput=self.link.callback(|e: InputData| AbilitiesMsg::NameInput(e.value)) />
+                            <button type="button" aria-label=t("Add group") title=t("Add group") onclick=self.link.callback(|_| AbilitiesMsg::AddAbility)>
+                                <i class=("fa", "fa-plus-square") aria-hidden="true"></i>
+                            </button>
+                            <button type="button" aria-label=t("Save age groups") title=t("Save age groups") onclick=self.link.callback(|_| AbilitiesMsg::SaveAbilities)>
+                                <i class=("fa", "fa-floppy-o") aria-hidden="true"></i>
+                            </button>
+                        </td></tr>
+                    </tfoot>
+                </table>
+            </>
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use yew::format::Json;
+use yew::prelude::*;
+use yew::services::{storage::Area, StorageService};
+
+use crate::data::*;
+use crate::web::i18n::{self, t, tf};
+use crate::web::workspace;
+use crate::web::Id;
+
+pub(crate) const ARCHIVE_KEY: &str = "archive_v1";
+
+/// The weeks that are over, kept for the history of who did what
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ArchiveStore {
+    pub inc: usize,
+    pub archive: Archive,
+}
+
+impl ArchiveStore {
+    pub fn restore(local_store: &mut StorageService) -> Option<Self> {
+        let from_store = local_store.restore(&workspace::key(ARCHIVE_KEY));
+        match from_store {
+            Json(Ok(archive)) => Some(archive),
+            Json(Err(err)) => {
+                error!("could not load from local store: {}", err);
+                None
+            }
+        }
+    }
+
+    pub fn restore_or_default(local_store: &mut StorageService) -> Self {
+        Self::restore(local_store).unwrap_or_default()
+    }
+
+    pub fn store(&mut self, local_store: &mut StorageService) {
+        self.inc += 1;
+        debug!("saving archive: {}", self.inc);
+        local_store.store(&workspace::key(ARCHIVE_KEY), Json(self as &Self));
+    }
+
+    /// Archives the week, replacing the one archived for the same start, returns the new version
+    pub fn add(local_store: &mut StorageService, week: Week) -> usize {
+        let mut stored = Self::restore_or_default(local_store);
+        stored.archive.add(week);
+        stored.store(local_store);
+        stored.inc
+    }
+}
+
+pub enum ArchiveMsg {
+    DeleteWeek(Id),
+}
+
+/// The archived weeks and how many jobs everyone did across them
+pub struct ArchiveModel {
+    stored: ArchiveStore,
+    archive_version: usize,
+    link: ComponentLink<Self>,
+}
+
+#[derive(Clone, Default, PartialEq, Properties)]
+pub struct ArchiveProps {
+    /// Changes when the chart archived a week
+    pub archive_version: usize,
+}
+
+impl ArchiveModel {
+    /// The first and last days of the week
+    fn dates(week: &Week) -> String {
+        match (week.start(), week.end()) {
+            (Some(start), Some(end)) => {
+                format!("{} – {}", i18n::full_date(start), i18n::full_date(end))
+            }
+            _ => String::new(),
+        }
+    }
+
+    fn view_week(&self, id: Id, week: &Week) -> Html {
+        let dates = Self::dates(week);
+        let delete = tf("Delete {}", &[&dates]);
+
+        html! {
+            <li>
+                { &dates }{ " " }
+                <button type="button" class="icon" aria-label=delete.clone() title=delete onclick=self.link.callback(move |_| ArchiveMsg::DeleteWeek(id))>
+                    <i class=("fa", "fa-trash", "fa-fw") aria-hidden="true"></i>
+                </button>
+            </li>
+        }
+    }
+}
+
+impl Component for ArchiveModel {
+    type Message = ArchiveMsg;
+    type Properties = ArchiveProps;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        Self {
+            stored: ArchiveStore::restore_or_default(&mut local_store),
+            archive_version: props.archive_version,
+            link,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            ArchiveMsg::DeleteWeek(id) => {
+                let dates = match self.stored.archive.weeks().get(id) {
+                    Some(week) => Self::dates(week),
+                    None => return false,
+                };
+                let question = tf(
+                    "Delete the chart for {}? Who did what will no longer count it.",
+                    &[&dates],
+                );
+                if !crate::web::confirm(&question) {
+                    return false;
+                }
+
+                self.stored.archive.remove(id);
+
+                debug!("deleted archived week: {}", id);
+                let mut local_store =
+                    StorageService::new(Area::Local).expect("failed to get storage");
+                self.stored.store(&mut local_store);
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.archive_version == props.archive_version {
+            return false;
+        }
+
+        debug!("reloading ArchiveModel");
+        self.archive_version = props.archive_version;
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        self.stored = ArchiveStore::restore_or_default(&mut local_store);
+        true
+    }
+
+    fn view(&self) -> Html {
+        let archive = &self.stored.archive;
+        if archive.is_empty() {
+            return html! {
+                <>
+                    <h2>{ t("Past charts") }</h2>
+                    <p>{ t("Charts are archived here once they're over, or with Archive this chart.") }</p>
+                </>
+            };
+        }
+
+        let fairness_row = |fairness: &Fairness| {
+            let jobs = fairness
+                .jobs()
+                .iter()
+                .map(|(job, count)| format!("{} ×{}", job, count))
+                .collect::<Vec<_>>();
+            let per_week = fairness.total() as f64 / fairness.weeks().max(1) as f64;
+            html! {
+                <tr>
+                    <th>{ fairness.person().name() }</th>
+                    <td>{ fairness.weeks() }</td>
+                    <td>{ fairness.done() }</td>
+                    <td>{ fairness.total() }</td>
+                    <td>{ format!("{:.1}", per_week) }</td>
+                    <td class="breakdown">{ jobs.join(", ") }</td>
+                </tr>
+            }
+        };
+
+        html! {
+            <>
+                <h2>{ t("Past charts") }</h2>
+                <ul class="archived_weeks">
+                    { for archive.weeks().iter().enumerate().map(|(i, w)| self.view_week(i, w)) }
+                </ul>
+                <h3>{ t("Who did what") }</h3>
+                <table>
+                    <thead>
+                        <tr><th>{ t("Person") }</th><th>{ t("Charts") }</th><th>{ t("Done") }</th><th>{ t("Jobs") }</th><th>{ t("Per chart") }</th><th>{" "}</th></tr>
+                    </thead>
+                    <tbody>
+                        { for Fairness::across(archive.weeks(), &archive.people()).iter().map(fairness_row) }
+                    </tbody>
+                </table>
+            </>
+        }
+    }
+}
@@ -1,96 +1,1291 @@
-use log::debug;
+use chrono::NaiveDate;
+use log::{debug, error, warn};
+use serde::{Deserialize, Serialize};
+use web_sys::DragEvent;
+use yew::callback::Callback;
+use yew::format::Json;
 use yew::prelude::*;
 
 use crate::data::*;
+use crate::web::abilities::AbilitiesStore;
+use crate::web::archive::ArchiveStore;
+use crate::web::day_editor::DayEditor;
+use crate::web::history;
+use crate::web::i18n::{self, t, tf};
+use crate::web::jobs::JobsStore;
 use crate::web::people::PeopleStore;
+use crate::web::qr::QrModel;
+use crate::web::remote;
+use crate::web::settings::{self, SettingsStore, SETTINGS_KEY};
+use crate::web::share;
+use crate::web::workspace;
 use yew::services::{storage::Area, StorageService};
 
+/// The most days a chart can cover
+const MAX_DAYS: usize = 31;
+pub(crate) const WEEK_KEY: &str = "week_v1";
+const LAYOUT_KEY: &str = "chart_layout_v1";
+
+/// How the week is laid out, only kept in this browser
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ChartLayout {
+    /// A row for each job and a column for each day
+    #[default]
+    Table,
+    /// A card for each day, for phones
+    DayCards,
+    /// A row for each person and a column for each day
+    PersonDays,
+}
+
+impl ChartLayout {
+    const ALL: [ChartLayout; 3] = [
+        ChartLayout::Table,
+        ChartLayout::DayCards,
+        ChartLayout::PersonDays,
+    ];
+
+    pub fn restore(local_store: &mut StorageService) -> Self {
+        match local_store.restore(LAYOUT_KEY) {
+            Json(Ok(layout)) => layout,
+            Json(Err(_)) => Self::default(),
+        }
+    }
+
+    pub fn store(self, local_store: &mut StorageService) {
+        debug!("saving layout: {:?}", self);
+        local_store.store(LAYOUT_KEY, Json(&self));
+    }
+
+    fn label(self) -> &'static str {
+        t(match self {
+            ChartLayout::Table => "Jobs by day",
+            ChartLayout::DayCards => "Day cards",
+            ChartLayout::PersonDays => "People by day",
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct Chart {
     people_version: usize,
+    jobs_version: usize,
+    abilities_version: usize,
+    history_version: usize,
+    settings: Settings,
+    abilities: Abilities,
+    jobs: Vec<Job>,
+    people: Vec<Person>,
+    /// The people in the order the week was calculated with, when it was balanced with past charts
+    scheduled: Option<Vec<Person>>,
     week: Week,
+    editing_day: Option<NaiveDate>,
+    /// The day and job whose people are being explained
+    explaining: Option<(usize, usize)>,
+    /// Why each slot of the job being explained was filled the way it was
+    explanations: Vec<Explanation>,
+    selected: Option<Assignment>,
+    /// The person being dragged to another cell
+    dragging: Option<Assignment>,
+    /// Name of the person whose jobs stand out
+    highlight: Option<String>,
+    /// Only the jobs for this age group are shown
+    ability_filter: Option<Ability>,
+    today_only: bool,
+    layout: ChartLayout,
+    error: Option<String>,
+    /// A chart from a shared link, which isn't stored or edited
+    shared: Option<SharedChart>,
+    /// Link to this chart, read only
+    share_link: String,
+    on_change: Option<Callback<()>>,
+    on_archive: Option<Callback<usize>>,
+    link: ComponentLink<Self>,
+}
+
+pub enum ChartMsg {
+    StartInput(String),
+    NumDaysInput(String),
+    EditDay(Option<NaiveDate>),
+    /// Shows why the people on a job on a day were picked
+    Explain(Option<(usize, usize)>),
+    OverrideDay(NaiveDate, DayOverride),
+    Select(Assignment),
+    DragStart(Assignment),
+    DragEnd,
+    /// Dropped on a person, to trade places with them
+    DropOnPerson(Assignment),
+    /// Dropped on a job on a day, to trade with anyone there
+    DropOnJob(usize, usize),
+    HighlightInput(String),
+    AbilityFilterInput(String),
+    ToggleTodayOnly,
+    Layout(ChartLayout),
+    ToggleDone(Assignment),
+    ToggleBalanceHistory,
+    Archive,
+    Ignore,
 }
 
 #[derive(Clone, Default, PartialEq, Properties)]
 pub struct ChartProps {
+    #[prop_or_default]
     pub people_version: usize,
+    #[prop_or_default]
+    pub jobs_version: usize,
+    #[prop_or_default]
+    pub abilities_version: usize,
+    /// Changes when an undo or redo replaced what's stored
+    #[prop_or_default]
+    pub history_version: usize,
+    /// Called after the settings or week are changed here
+    #[prop_or_default]
+    pub on_change: Option<Callback<()>>,
+    /// Called with the archive's version after a week was archived
+    #[prop_or_default]
+    pub on_archive: Option<Callback<usize>>,
+    /// Shows this chart read only instead of the stored one
+    #[prop_or_default]
+    pub shared: Option<SharedChart>,
+}
+
+/// Fingerprints of everything a stored week was calculated from
+///
+/// They're taken from the content rather than the local versions, so a week from the server is
+///  kept by every browser with the same people, jobs, age groups and settings.
+#[derive(Clone, Copy, Default, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct WeekSources {
+    pub people: u64,
+    pub jobs: u64,
+    pub abilities: u64,
+    pub settings: u64,
+}
+
+impl WeekSources {
+    fn new(people: &[Person], jobs: &[Job], abilities: &Abilities, settings: &Settings) -> Self {
+        Self {
+            people: fingerprint(people),
+            jobs: fingerprint(jobs),
+            abilities: fingerprint(abilities),
+            settings: fingerprint(settings),
+        }
+    }
+}
+
+/// FNV-1a of the JSON, the same in every browser for the same content
+fn fingerprint<T: Serialize + ?Sized>(document: &T) -> u64 {
+    serde_json::to_vec(document)
+        .unwrap_or_default()
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+/// The calculated week along with any edits, like swaps, made to it
+///
+/// The week is only kept while none of its sources change, after that it's calculated again and
+///  the trades and check offs that still fit are made again on the new week.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WeekStore {
+    pub inc: usize,
+    pub sources: WeekSources,
+    pub week: Week,
+    /// The people in the order the week was calculated with, when it was balanced with past charts
+    #[serde(default)]
+    pub scheduled: Option<Vec<Person>>,
+    /// Jobs missed in the chart before this one, they're made up in this one
+    #[serde(default)]
+    pub owed: Vec<Makeup>,
+    /// The owed jobs there wasn't a place for, they aren't carried over to the next chart
+    #[serde(default)]
+    pub unplaced: Vec<Makeup>,
+}
+
+impl WeekStore {
+    pub fn restore(local_store: &mut StorageService) -> Option<Self> {
+        let from_store = local_store.restore(&workspace::key(WEEK_KEY));
+        match from_store {
+            Json(Ok(week)) => Some(week),
+            Json(Err(err)) => {
+                error!("could not load from local store: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Stores the week in this browser only, e.g. one that was just calculated
+    ///
+    /// Every browser calculates the same week from the same roster, so only edits to it are sent
+    ///  to the server.
+    pub fn store(&mut self, local_store: &mut StorageService) {
+        self.inc += 1;
+        debug!("saving week: {}", self.inc);
+        local_store.store(&workspace::key(WEEK_KEY), Json(self as &Self));
+    }
+
+    /// Stores an edited week, e.g. after a trade, and sends it to the server
+    pub fn save(&mut self, local_store: &mut StorageService) {
+        self.store(local_store);
+        remote::push("week");
+    }
+
+    /// Stores a week as though it was calculated from what's stored now, e.g. an imported one
+    pub fn replace(local_store: &mut StorageService, week: Week) {
+        let people = PeopleStore::restore(local_store)
+            .map(|p| p.people)
+            .unwrap_or_else(crate::default_people);
+        let jobs = JobsStore::restore(local_store)
+            .map(|j| j.jobs)
+            .unwrap_or_else(crate::default_jobs);
+        let (_, abilities) = AbilitiesStore::restore_or_default(local_store);
+        let settings = SettingsStore::restore_or_default(local_store).settings;
+        let sources = WeekSources::new(&people, &jobs, &abilities, &settings);
+        let mut stored = WeekStore {
+            inc: Self::restore(local_store).map_or(0, |s| s.inc),
+            sources,
+            week,
+            scheduled: None,
+            owed: Vec::new(),
+            unplaced: Vec::new(),
+        };
+        stored.save(local_store);
+    }
 }
 
 impl Chart {
-    fn calculate() -> Self {
-        debug!("calculating new week");
+    fn load_shared(&mut self, shared: SharedChart) {
+        self.week = shared.week();
+        self.settings = shared.roster.settings.clone();
+        self.abilities = shared.roster.abilities.clone();
+        self.jobs = shared.roster.jobs.clone();
+        self.people = shared.roster.people.clone();
+        self.scheduled = None;
+        self.explaining = None;
+        self.explanations.clear();
+        self.selected = None;
+        self.share_link = share::share_link(&shared).unwrap_or_default();
+        self.shared = Some(shared);
+    }
+
+    fn update_share_link(&mut self) {
+        let roster = Roster {
+            people: self.people.clone(),
+            jobs: self.jobs.clone(),
+            abilities: self.abilities.clone(),
+            settings: self.settings.clone(),
+        };
+        let shared = SharedChart::balanced(roster, self.scheduled.clone(), &self.week);
+        self.share_link = share::share_link(&shared).unwrap_or_default();
+    }
+
+    fn calculate(&mut self) {
         let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
 
-        let jobs = crate::default_jobs();
+        let settings = SettingsStore::restore_or_default(&mut local_store).settings;
+        let (abilities_version, abilities) = AbilitiesStore::restore_or_default(&mut local_store);
+        let (jobs_version, jobs) = JobsStore::restore(&mut local_store)
+            .map(|j| (j.inc, j.jobs))
+            .unwrap_or_else(|| (0, crate::default_jobs()));
         let (people_version, people) = PeopleStore::restore(&mut local_store)
             .map(|p| (p.inc, p.people))
             .unwrap_or_else(|| (0, crate::default_people()));
-        Self {
-            people_version: people_version,
-            week: crate::calculate(5, jobs, people),
+
+        let sources = WeekSources::new(&people, &jobs, &abilities, &settings);
+        let (week, scheduled) = match WeekStore::restore(&mut local_store) {
+            Some(stored) if stored.sources == sources => (stored.week, stored.scheduled),
+            stored => {
+                debug!("calculating new week");
+                let inc = stored.as_ref().map_or(0, |s| s.inc);
+                let (owed, before) = match stored {
+                    Some(stored) if Self::is_replaced(&stored.week, &settings) => {
+                        // makeups with no place last time are dropped, so they can't pile up
+                        let owed = stored.week.missed(&people);
+                        self.archive(&mut local_store, stored.week);
+                        (owed, None)
+                    }
+                    // the same chart calculated again, e.g. after someone was added
+                    Some(stored) => (stored.owed, Some(stored.week)),
+                    None => (Vec::new(), None),
+                };
+
+                let scheduled = if settings.balance_history() {
+                    let archive = ArchiveStore::restore_or_default(&mut local_store).archive;
+                    Some(crate::balance(&people, archive.weeks()))
+                } else {
+                    None
+                };
+                let mut week = crate::calculate(
+                    &settings,
+                    &abilities,
+                    jobs.clone(),
+                    scheduled.clone().unwrap_or_else(|| people.clone()),
+                );
+                let unplaced = week.add_makeups(&owed, settings.start().max(settings::today()));
+                if !unplaced.is_empty() {
+                    debug!("no place to make up {} jobs", unplaced.len());
+                }
+                if let Some(before) = before {
+                    let dropped = week.replay_swaps(&before);
+                    if dropped > 0 {
+                        warn!("dropped {} trades that no longer fit", dropped);
+                        self.error = Some(tf(
+                            "{} trades no longer fit the chart and were undone",
+                            &[&dropped],
+                        ));
+                    }
+                    week.keep_done(&before);
+                }
+
+                let mut stored = WeekStore {
+                    inc,
+                    sources,
+                    week,
+                    scheduled,
+                    owed,
+                    unplaced,
+                };
+                stored.store(&mut local_store);
+                (stored.week, stored.scheduled)
+            }
+        };
+        self.week = week;
+        self.scheduled = scheduled;
+
+        self.people_version = people_version;
+        self.jobs_version = jobs_version;
+        self.abilities_version = abilities_version;
+        self.settings = settings;
+        self.abilities = abilities;
+        self.jobs = jobs;
+        self.people = people;
+        self.explaining = None;
+        self.explanations.clear();
+        self.selected = None;
+        self.update_share_link();
+    }
+
+    /// true if the week is over and the chart was moved on past it
+    fn is_replaced(week: &Week, settings: &Settings) -> bool {
+        week.end()
+            .is_some_and(|end| end < settings::today() && end < settings.start())
+    }
+
+    /// Keeps the week, as it is now with any trades, in the past charts
+    fn archive(&self, local_store: &mut StorageService, week: Week) {
+        debug!("archiving week starting: {:?}", week.start());
+        let version = ArchiveStore::add(local_store, week);
+        if let Some(e) = self.on_archive.as_ref() {
+            e.emit(version)
         }
     }
-}
 
-impl Component for Chart {
-    type Message = ();
-    type Properties = ChartProps;
+    /// Trades the selected assignment with this one, or selects it if nothing is selected yet
+    fn select(&mut self, assignment: Assignment) -> ShouldRender {
+        self.error = None;
+        let selected = match self.selected.take() {
+            Some(selected) if selected != assignment => selected,
+            Some(_) => return true,
+            None => {
+                self.selected = Some(assignment);
+                return true;
+            }
+        };
 
-    fn create(_props: Self::Properties, _link: ComponentLink<Self>) -> Self {
-        debug!("creating Chart");
-        Self::calculate()
+        let label = tf(
+            "Swap {} and {}",
+            &[&self.name(selected), &self.name(assignment)],
+        );
+        self.trade(label, |week| week.swap(selected, assignment))
     }
 
-    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
-        false
+    /// Moves the dragged person onto a job, trading with whoever is there
+    fn drop_on_job(&mut self, day: usize, job: usize) -> ShouldRender {
+        self.error = None;
+        let from = match self.dragging.take() {
+            Some(from) => from,
+            None => return false,
+        };
+        if from.day == day && from.job == job {
+            return true;
+        }
+
+        let job_name = self
+            .week
+            .days()
+            .get(day)
+            .and_then(|d| d.jobs().get(job))
+            .map(|(j, _)| j.name().to_string())
+            .unwrap_or_default();
+        let label = tf("Move {} to {}", &[&self.name(from), &job_name]);
+        self.trade(label, |week| week.move_to(from, day, job).map(|_| ()))
     }
 
-    fn change(&mut self, props: Self::Properties) -> ShouldRender {
-        if self.people_version != props.people_version {
-            debug!("updating Chart");
-            *self = Self::calculate();
-            true
-        } else {
-            false
+    fn name(&self, assignment: Assignment) -> String {
+        self.week
+            .get(assignment)
+            .map(|(_, _, p)| p.name().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Makes a trade on the stored week, it's kept along with the week until the week is calculated again
+    fn trade<F: FnOnce(&mut Week) -> Result<(), SwapError>>(
+        &mut self,
+        label: String,
+        f: F,
+    ) -> ShouldRender {
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        let mut stored = match WeekStore::restore(&mut local_store) {
+            Some(stored) => stored,
+            None => return true,
+        };
+
+        match f(&mut stored.week) {
+            Ok(()) => {
+                debug!("{}", label);
+                history::tracked(&mut local_store, label, WEEK_KEY, |local_store| {
+                    stored.save(local_store)
+                });
+                self.week = stored.week;
+                self.update_share_link();
+                self.changed();
+            }
+            Err(err) => {
+                warn!("could not trade: {}", err);
+                self.error = Some(err.to_string());
+            }
         }
+        true
     }
 
-    fn view(&self) -> Html {
-        let header = |name: &str| {
+    fn update_settings<F: FnOnce(&mut Settings) -> bool>(
+        &mut self,
+        label: String,
+        f: F,
+    ) -> ShouldRender {
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        let mut settings = SettingsStore::restore_or_default(&mut local_store);
+        if !f(&mut settings.settings) {
+            return false;
+        }
+
+        history::tracked(&mut local_store, label, SETTINGS_KEY, |local_store| {
+            settings.store(local_store)
+        });
+        self.calculate();
+        self.changed();
+        true
+    }
+
+    fn changed(&self) {
+        if let Some(e) = self.on_change.as_ref() {
+            e.emit(())
+        }
+    }
+
+    fn view_settings(&self) -> Html {
+        if self.shared.is_some() {
+            return html! {
+                <div class="chart_settings">
+                    { tf("Starting {} for {} days", &[&i18n::full_date(self.settings.start()), &self.settings.num_days()]) }
+                </div>
+            };
+        }
+
+        html! {
+            <div class="chart_settings">
+                <label>{ t("Starting") }{ " " }
+                    <input type="date" value={self.settings.start().format("%Y-%m-%d").to_string()} onchange=self.link.callback(|e| match e {
+                        ChangeData::Value(v) => ChartMsg::StartInput(v),
+                        _ => unreachable!(),
+                    }) />
+                </label>
+                <label>{ " " }{ t("for") }{ " " }
+                    <input type="number" min="1" max={MAX_DAYS.to_string()} value={self.settings.num_days().to_string()} onchange=self.link.callback(|e| match e {
+                        ChangeData::Value(v) => ChartMsg::NumDaysInput(v),
+                        _ => unreachable!(),
+                    }) />
+                    { " " }{ t("days") }
+                </label>
+                <label>
+                    <input type="checkbox" checked=self.settings.balance_history() onclick=self.link.callback(|_| ChartMsg::ToggleBalanceHistory) />
+                    { " " }{ t("Balance with past charts") }
+                </label>
+                <button type="button" onclick=self.link.callback(|_| ChartMsg::Archive)>{ t("Archive this chart") }</button>
+            </div>
+        }
+    }
+
+    /// The days to show, just today if that's asked for and today is in the chart
+    fn shown_days(&self) -> Vec<(usize, &Day)> {
+        let days = self.week.days().iter().enumerate();
+        if self.today_only {
+            let today = settings::today();
+            let shown: Vec<_> = days.clone().filter(|(_, d)| d.date() == today).collect();
+            if !shown.is_empty() {
+                return shown;
+            }
+        }
+        days.collect()
+    }
+
+    /// false if the assignment is for an age group that's filtered out
+    fn is_shown(&self, assignment: Assignment) -> bool {
+        match self.ability_filter {
+            Some(ref ability) => self
+                .week
+                .get(assignment)
+                .is_some_and(|(_, slot, _)| slot.ability() == ability),
+            None => true,
+        }
+    }
+
+    fn is_highlighted(&self, person: &Person) -> bool {
+        self.highlight.as_deref() == Some(person.name())
+    }
+
+    fn view_filters(&self) -> Html {
+        let person_option = |person: &Person| {
+            let name = person.name().to_string();
             html! {
-                <th>{ format!("{}", name) }</th>
+                <option value={name.clone()} selected=self.is_highlighted(person)>{ name }</option>
             }
         };
-        let people_cell = |people: &[Person]| {
-            let mut people_str = String::new();
-            for person in people {
-                people_str.push_str(person.name());
-                people_str.push_str(", ");
-            }
-
+        let ability_option = |ability: &Ability| {
             html! {
-                <td>{ people_str }</td>
+                <option value={ability.to_str().to_string()} selected=self.ability_filter.as_ref() == Some(ability)>{ i18n::ability_name(ability) }</option>
             }
         };
-        let job_row = |(job_idx, job): (usize, &Job)| {
-            let days = self.week.days();
+        let today = settings::today();
+        let not_today = if self.today_only && !self.week.days().iter().any(|d| d.date() == today) {
+            html! { <span>{ t("today isn't in this chart") }</span> }
+        } else {
+            html! {}
+        };
+
+        html! {
+            <div class="chart_filters">
+                <label>{ t("Highlight") }{ " " }
+                    <select onchange=self.link.callback(|e| match e {
+                        ChangeData::Select(se) => ChartMsg::HighlightInput(se.value()),
+                        _ => unreachable!(),
+                    })>
+                        <option value="" selected=self.highlight.is_none()>{ t("no one") }</option>
+                        { for self.people.iter().map(person_option) }
+                    </select>
+                </label>
+                <label>{ " " }{ t("Show") }{ " " }
+                    <select onchange=self.link.callback(|e| match e {
+                        ChangeData::Select(se) => ChartMsg::AbilityFilterInput(se.value()),
+                        _ => unreachable!(),
+                    })>
+                        <option value="" selected=self.ability_filter.is_none()>{ t("every age group") }</option>
+                        { for self.abilities.iter().map(ability_option) }
+                    </select>
+                </label>
+                <label>
+                    <input type="checkbox" checked=self.today_only onclick=self.link.callback(|_| ChartMsg::ToggleTodayOnly) />
+                    { " " }{ t("Today only") }{ " " }
+                </label>
+                { not_today }
+                <span class="layouts">
+                    { for ChartLayout::ALL.iter().map(|&layout| html! {
+                        <button disabled=self.layout == layout onclick=self.link.callback(move |_| ChartMsg::Layout(layout))>{ layout.label() }</button>
+                    }) }
+                </span>
+            </div>
+        }
+    }
+
+    fn view_error(&self) -> Html {
+        match self.error {
+            Some(ref error) => html! { <div class="error">{ error }</div> },
+            None => html! {},
+        }
+    }
+
+    /// Counts of everyone's jobs, so the effect of any trades can be seen
+    fn view_fairness(&self) -> Html {
+        let fairness_row = |fairness: &Fairness| {
+            let jobs = fairness
+                .jobs()
+                .iter()
+                .map(|(job, count)| format!("{} ×{}", job, count))
+                .collect::<Vec<_>>();
             html! {
-                <tr>{ header(job.name()) } { for days.iter().map(|d| people_cell(d.get_job_people(job_idx))) }</tr>
+                <tr>
+                    <th>{ fairness.person().name() }</th>
+                    <td>{ fairness.total() }</td>
+                    <td class="breakdown">{ jobs.join(", ") }</td>
+                </tr>
             }
         };
 
         html! {
             <>
-                <h2>{"Job Chart"}</h2>
+                <h3>{ t("Who does what") }</h3>
                 <table>
                     <thead>
-                        <tr><th>{"Job"}</th> { for self.week.days().iter().map(|d| header(d.name())) }</tr>
+                        <tr><th>{ t("Person") }</th><th>{ t("Jobs") }</th><th>{" "}</th></tr>
                     </thead>
                     <tbody>
-                        { for self.week.jobs().enumerate().map(|j| job_row(j)) }
+                        { for Fairness::of(&self.week, &self.people).iter().map(fairness_row) }
                     </tbody>
                 </table>
             </>
         }
     }
+
+    fn view_day_editor(&self) -> Html {
+        let (day_idx, date) = match self.editing_day.and_then(|date| {
+            self.settings
+                .dates()
+                .position(|d| d == date)
+                .map(|idx| (idx, date))
+        }) {
+            Some(day) => day,
+            None => return html! {},
+        };
+
+        let regular = self
+            .jobs
+            .iter()
+            .filter(|j| j.recurrence().occurs(day_idx, date))
+            .cloned()
+            .collect::<Vec<_>>();
+        let day_override = self
+            .settings
+            .day_override(date)
+            .cloned()
+            .unwrap_or_default();
+
+        html! {
+            <DayEditor
+                date=date
+                regular=regular
+                day_override=day_override
+                abilities=self.abilities.clone()
+                on_change=self.link.callback(|(date, o)| ChartMsg::OverrideDay(date, o))
+                on_close=self.link.callback(|_| ChartMsg::EditDay(None)) />
+        }
+    }
+    fn view_person(&self, assignment: Assignment, person: &Person) -> Html {
+        if !self.is_shown(assignment) {
+            return html! {};
+        }
+
+        let highlighted = if self.is_highlighted(person) {
+            "highlighted"
+        } else {
+            ""
+        };
+        let selected = if self.selected == Some(assignment) {
+            "selected"
+        } else {
+            ""
+        };
+        let swapped = if self.week.is_swapped(assignment) {
+            "swapped"
+        } else {
+            ""
+        };
+
+        let is_done = self.week.is_done(assignment);
+        let done = if is_done { "done" } else { "" };
+        let makeup = if self.week.is_makeup(assignment) {
+            "makeup"
+        } else {
+            ""
+        };
+        let title = if makeup.is_empty() {
+            String::new()
+        } else {
+            tf("{} is making up a missed job", &[&person.name()])
+        };
+
+        if self.shared.is_some() {
+            return html! {
+                <span class=("person", swapped, highlighted, done, makeup) title=title>{ person.name() }</span>
+            };
+        }
+
+        let dragging = if self.dragging == Some(assignment) {
+            "dragging"
+        } else {
+            ""
+        };
+        let name = person.name().to_string();
+        let check = if is_done {
+            tf("Uncheck {}", &[&name])
+        } else {
+            tf("Check off {}", &[&name])
+        };
+        let check_icon = if is_done {
+            "fa-check-square-o"
+        } else {
+            "fa-square-o"
+        };
+
+        html! {
+            <span class=("person", selected, swapped, dragging, highlighted, done, makeup)
+                title=title
+                draggable="true"
+                onclick=self.link.callback(move |_| ChartMsg::Select(assignment))
+                ondragstart=self.link.callback(move |e: DragEvent| {
+                    // some browsers only drag with something to carry
+                    if let Some(data) = e.data_transfer() {
+                        let _ = data.set_data("text/plain", &name);
+                    }
+                    ChartMsg::DragStart(assignment)
+                })
+                ondragend=self.link.callback(|_| ChartMsg::DragEnd)
+                ondrop=self.link.callback(move |e: DragEvent| {
+                    e.prevent_default();
+                    e.stop_propagation();
+                    ChartMsg::DropOnPerson(assignment)
+                })>
+                <button type="button" class=("icon", "check") aria-label=check.clone() title=check aria-pressed=is_done.to_string() onclick=self.link.callback(move |e: MouseEvent| {
+                    // checking off isn't selecting for a trade
+                    e.stop_propagation();
+                    ChartMsg::ToggleDone(assignment)
+                })>
+                    <i class=("fa", check_icon, "fa-fw") aria-hidden="true"></i>
+                </button>
+                { person.name() }
+            </span>
+        }
+    }
+
+    /// Everyone doing a job on a day, people can be dropped here from elsewhere in the chart
+    fn view_job_people(&self, day_idx: usize, job_idx: usize, people: &[Person]) -> Html {
+        let highlighted = if people.iter().any(|p| self.is_highlighted(p)) {
+            "highlighted"
+        } else {
+            ""
+        };
+        let people = people.iter().enumerate().map(|(slot, p)| {
+            self.view_person(
+                Assignment {
+                    day: day_idx,
+                    job: job_idx,
+                    slot,
+                },
+                p,
+            )
+        });
+
+        let why = html! {
+            <button type="button" class=("icon", "why") aria-label=t("Why these people?") title=t("Why these people?") onclick=self.link.callback(move |_| ChartMsg::Explain(Some((day_idx, job_idx))))>
+                <i class=("fa", "fa-question-circle-o", "fa-fw") aria-hidden="true"></i>
+            </button>
+        };
+
+        if self.shared.is_some() {
+            return html! {
+                <span class=("job_people", highlighted)>{ for people }{ why }</span>
+            };
+        }
+
+        html! {
+            <span class=("job_people", highlighted)
+                ondragover=self.link.callback(|e: DragEvent| {
+                    // allows dropping here
+                    e.prevent_default();
+                    ChartMsg::Ignore
+                })
+                ondrop=self.link.callback(move |e: DragEvent| {
+                    e.prevent_default();
+                    ChartMsg::DropOnJob(day_idx, job_idx)
+                })>
+                { for people }
+                { why }
+            </span>
+        }
+    }
+
+    /// Works out why the job being explained has the people it has, by scheduling the week again
+    fn explain(&mut self) {
+        self.explanations = match self.explaining {
+            Some((day_idx, job_idx)) => {
+                let people = self
+                    .scheduled
+                    .clone()
+                    .unwrap_or_else(|| self.people.clone());
+                crate::explain(&self.settings, &self.abilities, self.jobs.clone(), people)
+                    .into_iter()
+                    .filter(|e| e.assignment.day == day_idx && e.assignment.job == job_idx)
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+    }
+
+    /// The scheduler's reasons for each slot of the job being explained
+    fn view_explanation(&self) -> Html {
+        let (day_idx, job_idx) = match self.explaining {
+            Some(explaining) => explaining,
+            None => return html! {},
+        };
+        let (day, job) = match self
+            .week
+            .days()
+            .get(day_idx)
+            .and_then(|d| d.jobs().get(job_idx).map(|(j, _)| (d, j)))
+        {
+            Some(found) => found,
+            None => return html! {},
+        };
+
+        let candidate = |candidate: &Candidate| {
+            let reason = match candidate.reason {
+                Reason::Chosen => t("first in line of those who can do it").to_string(),
+                Reason::MissingTags(ref tags) => tf("doesn't have {}", &[&tags_to_string(tags)]),
+                Reason::LaterInLine => t("later in line").to_string(),
+            };
+            let chosen = if candidate.reason == Reason::Chosen {
+                "chosen"
+            } else {
+                ""
+            };
+            html! {
+                <li class=chosen>
+                    { tf("{}, {} jobs so far in the chart: {}", &[&candidate.name, &candidate.jobs_so_far, &reason]) }
+                </li>
+            }
+        };
+        let slot = |explanation: &Explanation| {
+            let group = i18n::ability_name(explanation.slot.ability());
+            let others = self
+                .people
+                .len()
+                .saturating_sub(explanation.candidates.len());
+            let chosen = explanation.chosen().map(|c| c.name.as_str());
+            let now = self
+                .week
+                .get(explanation.assignment)
+                .map(|(_, _, p)| p.name());
+            let changed = if self.week.is_makeup(explanation.assignment) {
+                tf(
+                    "{} was put in to make up a missed job.",
+                    &[&now.unwrap_or_default()],
+                )
+            } else if chosen.is_some() && now != chosen {
+                tf(
+                    "{} has it now, it was traded after the chart was made.",
+                    &[&now.unwrap_or_default()],
+                )
+            } else {
+                String::new()
+            };
+            let advanced = if explanation.advanced > 0 {
+                tf(
+                    "After the day before the {} line was moved on {} places without a job, so the big jobs don't always fall on the same people.",
+                    &[&group, &explanation.advanced],
+                )
+            } else {
+                String::new()
+            };
+            let summary = match chosen {
+                Some(chosen) => tf("{}: {}", &[&i18n::slot(&explanation.slot), &chosen]),
+                None => tf(
+                    "{}: nobody in the group can do it",
+                    &[&i18n::slot(&explanation.slot)],
+                ),
+            };
+
+            html! {
+                <li>
+                    <strong>{ summary }</strong>
+                    <p>{ tf("Only the {} group can fill this slot, {} people in other groups weren't considered.", &[&group, &others]) }{ " " }{ advanced }</p>
+                    <ol class="candidates">
+                        { for explanation.candidates.iter().map(candidate) }
+                    </ol>
+                    <p>{ changed }</p>
+                </li>
+            }
+        };
+
+        html! {
+            <div class="explanation">
+                <h3>
+                    { tf("Why these people for {} on {}", &[&job.name(), &i18n::day_name(day.date())]) }
+                    <button type="button" class="icon" aria-label=t("Close") title=t("Close") onclick=self.link.callback(|_| ChartMsg::Explain(None))>
+                        <i class=("fa", "fa-times", "fa-fw") aria-hidden="true"></i>
+                    </button>
+                </h3>
+                <p>{ t("Each age group takes turns in line, whoever's first in line and can do the job gets it and goes to the back.") }</p>
+                <ul>
+                    { for self.explanations.iter().map(slot) }
+                </ul>
+            </div>
+        }
+    }
+
+    fn view_day_name(&self, day: &Day) -> Html {
+        let date = day.date();
+        if self.shared.is_some() {
+            return html! { <>{ i18n::day_name(day.date()) }</> };
+        }
+        let edit = tf("Change the jobs on {}", &[&i18n::long_day_name(date)]);
+
+        html! {
+            <>
+                { i18n::day_name(day.date()) }
+                <button type="button" class="icon" aria-label=edit.clone() title=edit onclick=self.link.callback(move |_| ChartMsg::EditDay(Some(date)))>
+                    <i class=("fa", "fa-calendar", "fa-fw") aria-hidden="true"></i>
+                </button>
+            </>
+        }
+    }
+
+    fn special_day(&self, day: &Day) -> &'static str {
+        if self.settings.day_override(day.date()).is_some() {
+            "special_day"
+        } else {
+            ""
+        }
+    }
+
+    /// false if no one doing the job on any of the days is shown
+    fn is_job_shown(&self, days: &[(usize, &Day)], job: &Job) -> bool {
+        days.iter().any(|(i, d)| {
+            d.find_job(job).is_some_and(|job_idx| {
+                (0..job.people().len()).any(|slot| {
+                    self.is_shown(Assignment {
+                        day: *i,
+                        job: job_idx,
+                        slot,
+                    })
+                })
+            })
+        })
+    }
+
+    /// A row for each job with a column for each day
+    fn view_table(&self, days: &[(usize, &Day)]) -> Html {
+        let people_cell = |day_idx: usize, day: &Day, job: &Job| match day.find_job(job) {
+            Some(job_idx) => html! {
+                <td>{ self.view_job_people(day_idx, job_idx, &day.jobs()[job_idx].1) }</td>
+            },
+            None => html! { <td class="off_day"></td> },
+        };
+        let job_row = |job: &Job| {
+            // leave out jobs with no one left after filtering
+            if !self.is_job_shown(days, job) {
+                return html! {};
+            }
+
+            html! {
+                <tr><th>{ job.name() }</th> { for days.iter().map(|(i, d)| people_cell(*i, d, job)) }</tr>
+            }
+        };
+
+        html! {
+            <table>
+                <thead>
+                    <tr><th>{ t("Job") }</th> { for days.iter().map(|(_, d)| html! { <th class=self.special_day(d)>{ self.view_day_name(d) }</th> }) }</tr>
+                </thead>
+                <tbody>
+                    { for self.week.jobs().map(job_row) }
+                </tbody>
+            </table>
+        }
+    }
+
+    /// A card for each day listing its jobs, narrow enough for a phone
+    fn view_day_cards(&self, days: &[(usize, &Day)]) -> Html {
+        let day_card = |day_idx: usize, day: &Day| {
+            let job_item = |(job_idx, (job, people)): (usize, &(Job, Vec<Person>))| {
+                if !self.is_job_shown(&[(day_idx, day)], job) {
+                    return html! {};
+                }
+
+                html! {
+                    <li>
+                        <span class="job_name">{ job.name() }</span>
+                        { self.view_job_people(day_idx, job_idx, people) }
+                    </li>
+                }
+            };
+
+            html! {
+                <div class=("day_card", self.special_day(day))>
+                    <h3>{ self.view_day_name(day) }</h3>
+                    <ul>
+                        { for day.jobs().iter().enumerate().map(job_item) }
+                    </ul>
+                </div>
+            }
+        };
+
+        html! {
+            <div class="day_cards">
+                { for days.iter().map(|(i, d)| day_card(*i, d)) }
+            </div>
+        }
+    }
+
+    /// A row for each person with the jobs they have each day
+    fn view_person_days(&self, days: &[(usize, &Day)]) -> Html {
+        let person_cell = |day_idx: usize, day: &Day, person: &Person| {
+            let jobs = day
+                .jobs()
+                .iter()
+                .enumerate()
+                .flat_map(|(job_idx, (job, people))| {
+                    people
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, p)| p.name() == person.name())
+                        .map(move |(slot, _)| {
+                            (
+                                Assignment {
+                                    day: day_idx,
+                                    job: job_idx,
+                                    slot,
+                                },
+                                job,
+                            )
+                        })
+                })
+                .filter(|(assignment, _)| self.is_shown(*assignment))
+                .map(|(_, job)| job.name())
+                .collect::<Vec<_>>();
+
+            html! { <td>{ jobs.join(", ") }</td> }
+        };
+        let person_row = |person: &Person| {
+            let highlighted = if self.is_highlighted(person) {
+                "highlighted"
+            } else {
+                ""
+            };
+
+            html! {
+                <tr class=highlighted>
+                    <th>{ person.name() }</th>
+                    { for days.iter().map(|(i, d)| person_cell(*i, d, person)) }
+                </tr>
+            }
+        };
+
+        html! {
+            <table>
+                <thead>
+                    <tr><th>{ t("Person") }</th> { for days.iter().map(|(_, d)| html! { <th class=self.special_day(d)>{ self.view_day_name(d) }</th> }) }</tr>
+                </thead>
+                <tbody>
+                    { for self.people.iter().map(person_row) }
+                </tbody>
+            </table>
+        }
+    }
+}
+
+impl Component for Chart {
+    type Message = ChartMsg;
+    type Properties = ChartProps;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        debug!("creating Chart");
+        let mut chart = Self {
+            people_version: 0,
+            jobs_version: 0,
+            abilities_version: 0,
+            history_version: props.history_version,
+            settings: Settings::default(),
+            abilities: Abilities::default(),
+            jobs: Vec::new(),
+            people: Vec::new(),
+            scheduled: None,
+            week: Week::new(Vec::new()),
+            editing_day: None,
+            explaining: None,
+            explanations: Vec::new(),
+            selected: None,
+            dragging: None,
+            highlight: None,
+            ability_filter: None,
+            today_only: false,
+            layout: ChartLayout::restore(
+                &mut StorageService::new(Area::Local).expect("failed to get storage"),
+            ),
+            error: None,
+            shared: None,
+            share_link: String::new(),
+            on_change: props.on_change.clone(),
+            on_archive: props.on_archive.clone(),
+            link,
+        };
+        match props.shared {
+            Some(shared) => chart.load_shared(shared),
+            None => chart.calculate(),
+        }
+        chart
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            ChartMsg::StartInput(start) => {
+                debug!("chart start: {}", start);
+                match NaiveDate::parse_from_str(&start, "%Y-%m-%d") {
+                    Ok(start) => self.update_settings(t("Change the start").to_string(), |s| {
+                        let changed = s.start() != start;
+                        s.set_start(start);
+                        changed
+                    }),
+                    Err(_) => false,
+                }
+            }
+            ChartMsg::NumDaysInput(num_days) => {
+                debug!("chart days: {}", num_days);
+                match num_days.parse::<usize>() {
+                    Ok(num_days) if num_days > 0 && num_days <= MAX_DAYS => {
+                        self.update_settings(t("Change the number of days").to_string(), |s| {
+                            let changed = s.num_days() != num_days;
+                            s.set_num_days(num_days);
+                            changed
+                        })
+                    }
+                    _ => false,
+                }
+            }
+            ChartMsg::Explain(explaining) if self.explaining == explaining => false,
+            ChartMsg::Explain(explaining) => {
+                self.explaining = explaining;
+                self.explain();
+                true
+            }
+            ChartMsg::EditDay(date) => {
+                let changed = self.editing_day != date;
+                self.editing_day = date;
+                changed
+            }
+            ChartMsg::OverrideDay(date, day_override) => {
+                debug!("overriding jobs on {}", date);
+                let label = tf("Change the jobs on {}", &[&i18n::day_name(date)]);
+                self.update_settings(label, |s| {
+                    let changed = s.day_override(date).cloned().unwrap_or_default() != day_override;
+                    s.update_override(date, |o| *o = day_override);
+                    changed
+                })
+            }
+            ChartMsg::Select(assignment) => self.select(assignment),
+            ChartMsg::DragStart(assignment) => {
+                self.selected = None;
+                self.dragging = Some(assignment);
+                true
+            }
+            ChartMsg::DragEnd => self.dragging.take().is_some(),
+            ChartMsg::DropOnPerson(assignment) => match self.dragging.take() {
+                Some(from) => {
+                    self.selected = Some(from);
+                    self.select(assignment)
+                }
+                None => false,
+            },
+            ChartMsg::DropOnJob(day, job) => self.drop_on_job(day, job),
+            ChartMsg::HighlightInput(name) => {
+                self.highlight = Some(name).filter(|n| !n.is_empty());
+                true
+            }
+            ChartMsg::AbilityFilterInput(ability) => {
+                self.ability_filter = Some(ability).filter(|a| !a.is_empty()).map(Ability::new);
+                true
+            }
+            ChartMsg::ToggleTodayOnly => {
+                self.today_only = !self.today_only;
+                true
+            }
+            ChartMsg::Layout(layout) => {
+                if self.layout == layout {
+                    return false;
+                }
+
+                let mut local_store =
+                    StorageService::new(Area::Local).expect("failed to get storage");
+                layout.store(&mut local_store);
+                self.layout = layout;
+                true
+            }
+            ChartMsg::ToggleDone(assignment) => {
+                let name = self.name(assignment);
+                let label = if self.week.is_done(assignment) {
+                    tf("Uncheck {}", &[&name])
+                } else {
+                    tf("Check off {}", &[&name])
+                };
+                self.trade(label, |week| week.toggle_done(assignment).map(|_| ()))
+            }
+            ChartMsg::ToggleBalanceHistory => {
+                let label = if self.settings.balance_history() {
+                    t("Stop balancing with past charts")
+                } else {
+                    t("Balance with past charts")
+                };
+                self.update_settings(label.to_string(), |s| {
+                    s.set_balance_history(!s.balance_history());
+                    true
+                })
+            }
+            ChartMsg::Archive if self.shared.is_some() => false,
+            ChartMsg::Archive => {
+                let mut local_store =
+                    StorageService::new(Area::Local).expect("failed to get storage");
+                self.archive(&mut local_store, self.week.clone());
+                false
+            }
+            ChartMsg::Ignore => false,
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if let Some(shared) = props.shared {
+            if self.shared.as_ref() == Some(&shared) {
+                return false;
+            }
+
+            self.load_shared(shared);
+            return true;
+        }
+
+        if self.history_version != props.history_version
+            || self.people_version != props.people_version
+            || self.jobs_version != props.jobs_version
+            || self.abilities_version != props.abilities_version
+        {
+            debug!("updating Chart");
+            self.history_version = props.history_version;
+            self.calculate();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let days = self.shown_days();
+        let chart = match self.layout {
+            ChartLayout::Table => self.view_table(&days),
+            ChartLayout::DayCards => self.view_day_cards(&days),
+            ChartLayout::PersonDays => self.view_person_days(&days),
+        };
+
+        html! {
+            <>
+                <h2>{ t("Job Chart") }</h2>
+                { self.view_settings() }
+                { self.view_day_editor() }
+                { self.view_filters() }
+                { chart }
+                { self.view_error() }
+                { self.view_explanation() }
+                <div class="share_qr">
+                    <QrModel link=self.share_link.clone() />
+                    <p>{ t("Scan to see the chart") }</p>
+                </div>
+                { self.view_fairness() }
+            </>
+        }
+    }
 }
@@ -0,0 +1,200 @@
+use chrono::NaiveDate;
+use log::debug;
+use yew::callback::Callback;
+use yew::prelude::*;
+
+use crate::data::*;
+use crate::web::i18n::{self, t, tf};
+
+/// Editor for the special jobs of a single day, shown under the chart header
+pub struct DayEditor {
+    props: DayEditorProps,
+    name: String,
+    ability: Ability,
+    count: usize,
+    error: Option<Invalid>,
+    link: ComponentLink<Self>,
+}
+
+#[derive(Clone, Default, PartialEq, Properties)]
+pub struct DayEditorProps {
+    pub date: NaiveDate,
+    /// the jobs that would normally be done on the day
+    pub regular: Vec<Job>,
+    pub day_override: DayOverride,
+    pub abilities: Abilities,
+    pub on_change: Option<Callback<(NaiveDate, DayOverride)>>,
+    pub on_close: Option<Callback<()>>,
+}
+
+pub enum DayEditorMsg {
+    ToggleSkip(String),
+    NameInput(String),
+    AbilityInput(Ability),
+    CountInput(usize),
+    AddExtra,
+    RemoveExtra(usize),
+    Close,
+}
+
+impl DayEditor {
+    fn emit(&self, day_override: DayOverride) {
+        if let Some(c) = self.props.on_change.as_ref() {
+            c.emit((self.props.date, day_override))
+        }
+    }
+}
+
+impl Component for DayEditor {
+    type Message = DayEditorMsg;
+    type Properties = DayEditorProps;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let ability = props.abilities.first().cloned().unwrap_or_default();
+        Self {
+            props,
+            name: String::new(),
+            ability,
+            count: 1,
+            error: None,
+            link,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            DayEditorMsg::ToggleSkip(job) => {
+                debug!("toggling {} on {}", job, self.props.date);
+                let mut day_override = self.props.day_override.clone();
+                day_override.toggle_skip(&job);
+                self.emit(day_override);
+                false
+            }
+            DayEditorMsg::NameInput(name) => {
+                self.name = name;
+                self.error.take().is_some()
+            }
+            DayEditorMsg::AbilityInput(ability) => {
+                self.ability = ability;
+                false
+            }
+            DayEditorMsg::CountInput(count) => {
+                self.count = count;
+                false
+            }
+            DayEditorMsg::AddExtra => {
+                let name = self.name.trim();
+                if name.is_empty() || self.count == 0 {
+                    return false;
+                }
+
+                debug!("adding {} on {}", name, self.props.date);
+                let job = Job::new(name, vec![self.ability.clone(); self.count]);
+                let mut day_override = self.props.day_override.clone();
+                if !day_override.add_extra(job, &self.props.regular) {
+                    debug!("{} is already a job on {}", name, self.props.date);
+                    self.error = Some(Invalid::DuplicateName);
+                    return true;
+                }
+                self.name.clear();
+                self.emit(day_override);
+                true
+            }
+            DayEditorMsg::RemoveExtra(idx) => {
+                let mut day_override = self.props.day_override.clone();
+                if day_override.remove_extra(idx).is_some() {
+                    self.emit(day_override);
+                }
+                false
+            }
+            DayEditorMsg::Close => {
+                if let Some(c) = self.props.on_close.as_ref() {
+                    c.emit(())
+                }
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.props != props {
+            if self.props.date != props.date {
+                self.name.clear();
+                self.error = None;
+            }
+            if !props.abilities.contains(&self.ability) {
+                self.ability = props.abilities.first().cloned().unwrap_or_default();
+            }
+            self.props = props;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let regular_job = |job: &Job| {
+            let name = job.name().to_string();
+            html! {
+                <li>
+                    <label>
+                        <input type="checkbox" checked={!self.props.day_override.is_skipped(job)} onclick=self.link.callback(move |_| DayEditorMsg::ToggleSkip(name.clone())) />
+                        { job.name() }
+                    </label>
+                </li>
+            }
+        };
+        let extra_job = |(idx, job): (usize, &Job)| {
+            let slots = job.people().iter().map(i18n::slot).collect::<Vec<_>>();
+            let remove = tf("Delete {}", &[&job.name()]);
+            html! {
+                <li>
+                    { format!("{} ({})", job.name(), slots.join(", ")) }
+                    <button type="button" class="icon" aria-label=remove.clone() title=remove onclick=self.link.callback(move |_| DayEditorMsg::RemoveExtra(idx))>
+                        <i class=("fa", "fa-trash", "fa-fw") aria-hidden="true"></i>
+                    </button>
+                </li>
+            }
+        };
+        let select_ability = |ability: &Ability| {
+            html! {
+                <option value={ability.to_str()} selected={*ability == self.ability}>{ i18n::ability_name(ability) }</option>
+            }
+        };
+
+        let error = match self.error {
+            Some(error) => html! { <div class="field_error error">{ t(error.message()) }</div> },
+            None => html! {},
+        };
+
+        html! {
+            <div class="day_editor">
+                <h3>
+                    { i18n::long_day_name(self.props.date) }
+                    <button type="button" class="icon" aria-label=t("Close") title=t("Close") onclick=self.link.callback(|_| DayEditorMsg::Close)>
+                        <i class=("fa", "fa-times", "fa-fw") aria-hidden="true"></i>
+                    </button>
+                </h3>
+                <ul class="slots">
+                    { for self.props.regular.iter().map(regular_job) }
+                    { for self.props.day_override.extra().iter().enumerate().map(extra_job) }
+                </ul>
+                <input type="text" placeholder=t("One-off job") value={&self.name} oninput=self.link.callback(|e: InputData| DayEditorMsg::NameInput(e.value)) />
+                <input type="number" min="1" value={self.count.to_string()} onchange=self.link.callback(|e| match e {
+                    ChangeData::Value(v) => DayEditorMsg::CountInput(v.parse().unwrap_or(0)),
+                    _ => unreachable!(),
+                }) />
+                <select onchange=self.link.callback(|e| match e {
+                    ChangeData::Select(se) => DayEditorMsg::AbilityInput(Ability::new(se.value())),
+                    _ => unreachable!(),
+                })>
+                    { for self.props.abilities.iter().map(select_ability) }
+                </select>
+                <button type="button" aria-label=t("Add the one-off job") title=t("Add the one-off job") onclick=self.link.callback(|_| DayEditorMsg::AddExtra)>
+                    <i class=("fa", "fa-plus-square") aria-hidden="true"></i>
+                </button>
+                { error }
+            </div>
+        }
+    }
+}
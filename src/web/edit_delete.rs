@@ -0,0 +1,160 @@
+use std::time::Duration;
+
+use log::debug;
+use yew::callback::Callback;
+use yew::prelude::*;
+use yew::services::timeout::{TimeoutService, TimeoutTask};
+
+use crate::web::i18n::tf;
+use crate::web::{Id, IsEditting};
+
+/// EditDelete Component for a person or job row
+pub(crate) struct EditDelete {
+    id: Id,
+    label: String,
+    is_editting: IsEditting,
+    on_edit: Option<Callback<Id>>,
+    on_delete: Option<Callback<Id>>,
+    on_save: Option<Callback<Id>>,
+    on_cancel: Option<Callback<Id>>,
+    edit_button: NodeRef,
+    /// Puts the focus back on edit once the row is drawn after editing
+    focus_task: Option<TimeoutTask>,
+    link: ComponentLink<Self>,
+}
+
+#[derive(Clone, PartialEq, Default, Properties)]
+pub(crate) struct EditDeleteProps {
+    pub id: Id,
+    /// What the row is, e.g. the person's name, for screen readers
+    #[prop_or_default]
+    pub label: String,
+    pub is_editting: IsEditting,
+    pub on_edit: Option<Callback<Id>>,
+    pub on_delete: Option<Callback<Id>>,
+    /// Shows save and cancel for the row while editing
+    #[prop_or_default]
+    pub on_save: Option<Callback<Id>>,
+    #[prop_or_default]
+    pub on_cancel: Option<Callback<Id>>,
+}
+
+pub(crate) enum EditDeleteMsg {
+    Edit,
+    Delete,
+    Save,
+    Cancel,
+    Focus,
+}
+
+impl EditDelete {
+    fn emit(&self, callback: &Option<Callback<Id>>) {
+        if let Some(c) = callback.as_ref() {
+            c.emit(self.id);
+        }
+    }
+
+    fn button(&self, icon: &'static str, label: String, msg: fn() -> EditDeleteMsg) -> Html {
+        html! {
+            <button type="button" class="icon" aria-label=label.clone() title=label onclick=self.link.callback(move |_| msg())>
+                <i class=("fa", icon, "fa-fw") aria-hidden="true"></i>
+            </button>
+        }
+    }
+}
+
+impl Component for EditDelete {
+    type Message = EditDeleteMsg;
+    type Properties = EditDeleteProps;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            id: props.id,
+            label: props.label,
+            is_editting: props.is_editting,
+            on_edit: props.on_edit,
+            on_delete: props.on_delete,
+            on_save: props.on_save,
+            on_cancel: props.on_cancel,
+            edit_button: NodeRef::default(),
+            focus_task: None,
+            link,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            EditDeleteMsg::Edit => {
+                debug!("editting: {}", self.id);
+                if !self.is_editting {
+                    self.emit(&self.on_edit);
+                }
+            }
+            EditDeleteMsg::Delete => {
+                debug!("deleting: {}", self.id);
+                self.emit(&self.on_delete);
+            }
+            EditDeleteMsg::Save => {
+                debug!("saving: {}", self.id);
+                self.emit(&self.on_save);
+            }
+            EditDeleteMsg::Cancel => {
+                debug!("cancelling: {}", self.id);
+                self.emit(&self.on_cancel);
+            }
+            EditDeleteMsg::Focus => {
+                self.focus_task = None;
+                crate::web::focus(&self.edit_button, true);
+            }
+        }
+
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let mut render = false;
+        if self.is_editting != props.is_editting {
+            if self.is_editting {
+                // the focused input is gone once the row is drawn, so focus the row's edit button
+                self.focus_task = Some(TimeoutService::new().spawn(
+                    Duration::from_millis(0),
+                    self.link.callback(|_| EditDeleteMsg::Focus),
+                ));
+            }
+            self.is_editting = props.is_editting;
+            render = true;
+        }
+
+        if self.label != props.label {
+            self.label = props.label;
+            render = true;
+        }
+        render
+    }
+
+    fn view(&self) -> Html {
+        let delete = self.button("fa-trash", tf("Delete {}", &[&self.label]), || {
+            EditDeleteMsg::Delete
+        });
+
+        if self.is_editting && self.on_save.is_some() {
+            return html! {
+                <div class="edit_delete">
+                    { self.button("fa-floppy-o", tf("Save {}", &[&self.label]), || EditDeleteMsg::Save) }
+                    { self.button("fa-times", tf("Cancel editing {}", &[&self.label]), || EditDeleteMsg::Cancel) }
+                    { delete }
+                </div>
+            };
+        }
+
+        let label = tf("Edit {}", &[&self.label]);
+        html! {
+            <div class="edit_delete">
+                <button type="button" class="icon" ref=self.edit_button.clone() disabled=self.is_editting aria-label=label.clone() title=label onclick=self.link.callback(|_| EditDeleteMsg::Edit)>
+                    <i class=("fa", "fa-pencil-square-o", "fa-fw") aria-hidden="true"></i>
+                </button>
+                { delete }
+            </div>
+        }
+    }
+}
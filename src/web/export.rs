@@ -0,0 +1,201 @@
+use log::debug;
+use yew::prelude::*;
+use yew::services::reader::{FileData, ReaderService, ReaderTask};
+use yew::services::{storage::Area, StorageService};
+
+use crate::data::*;
+use crate::web::abilities::AbilitiesStore;
+use crate::web::chart::WeekStore;
+use crate::web::i18n::{t, tf};
+use crate::web::jobs::JobsStore;
+use crate::web::people::PeopleStore;
+use crate::web::settings::SettingsStore;
+use crate::web::share;
+use crate::web::sync::PeopleLogStore;
+
+const EXPORT_FILE: &str = "kp-chart.json";
+
+pub enum ExportMsg {
+    ImportFile(ChangeData),
+    Loaded(FileData),
+}
+
+/// Export the roster as a JSON file, or replace it with a previously exported one
+pub struct ExportModel {
+    reader: ReaderService,
+    task: Option<ReaderTask>,
+    error: Option<String>,
+    roster_version: usize,
+    /// The stored roster as a data URL for the download
+    href: String,
+    share_link: String,
+    link: ComponentLink<Self>,
+}
+
+#[derive(Clone, Default, PartialEq, Properties)]
+pub struct ExportProps {
+    /// Changes when the roster or the chart were changed
+    pub roster_version: usize,
+}
+
+/// The roster as it is currently saved in local storage
+pub(crate) fn stored_roster(local_store: &mut StorageService) -> Roster {
+    Roster {
+        people: PeopleStore::restore(local_store)
+            .map(|p| p.people)
+            .unwrap_or_else(crate::default_people),
+        jobs: JobsStore::restore(local_store)
+            .map(|j| j.jobs)
+            .unwrap_or_else(crate::default_jobs),
+        abilities: AbilitiesStore::restore_or_default(local_store).1,
+        settings: SettingsStore::restore_or_default(local_store).settings,
+    }
+}
+
+/// Saves each part of the roster, carrying the stored versions forward so the chart recalculates
+pub(crate) fn store_roster(roster: Roster, local_store: &mut StorageService) {
+    let inc = PeopleStore::restore(local_store).map_or(0, |p| p.inc);
+    PeopleLogStore::record(local_store, Some(PeopleOp::Replace(roster.people.clone())));
+    PeopleStore {
+        inc,
+        people: roster.people,
+    }
+    .store(local_store);
+
+    let inc = JobsStore::restore(local_store).map_or(0, |j| j.inc);
+    JobsStore {
+        inc,
+        jobs: roster.jobs,
+    }
+    .store(local_store);
+
+    let (inc, _) = AbilitiesStore::restore_or_default(local_store);
+    AbilitiesStore {
+        inc,
+        abilities: roster.abilities,
+    }
+    .store(local_store);
+
+    let SettingsStore { inc, .. } = SettingsStore::restore_or_default(local_store);
+    SettingsStore {
+        inc,
+        settings: roster.settings,
+    }
+    .store(local_store);
+}
+
+impl ExportModel {
+    /// Reads the stored roster and chart for the download and share links
+    fn load(&mut self) {
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        let roster = stored_roster(&mut local_store);
+        let json = serde_json::to_string_pretty(&roster).expect("roster is always serializable");
+        self.href = format!(
+            "data:application/json;charset=utf-8,{}",
+            js_sys::encode_uri_component(&json)
+        );
+
+        // the chart as it was calculated, which with balancing is from the people in a new order
+        let shared = match WeekStore::restore(&mut local_store) {
+            Some(stored) => SharedChart::balanced(roster, stored.scheduled, &stored.week),
+            None => {
+                let week = roster.calculate();
+                SharedChart::balanced(roster, None, &week)
+            }
+        };
+        self.share_link = share::share_link(&shared).unwrap_or_default();
+    }
+}
+
+impl Component for ExportModel {
+    type Message = ExportMsg;
+    type Properties = ExportProps;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut export = Self {
+            reader: ReaderService::new(),
+            task: None,
+            error: None,
+            roster_version: props.roster_version,
+            href: String::new(),
+            share_link: String::new(),
+            link,
+        };
+        export.load();
+        export
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            ExportMsg::ImportFile(ChangeData::Files(files)) => {
+                let file = match files.get(0) {
+                    Some(file) => file,
+                    None => return false,
+                };
+
+                debug!("importing {}", file.name());
+                let callback = self.link.callback(ExportMsg::Loaded);
+                match self.reader.read_file(file, callback) {
+                    Ok(task) => self.task = Some(task),
+                    Err(err) => self.error = Some(err.to_string()),
+                }
+                true
+            }
+            ExportMsg::ImportFile(_) => false,
+            ExportMsg::Loaded(data) => {
+                self.task = None;
+                match serde_json::from_slice::<Roster>(&data.content) {
+                    Ok(roster) => {
+                        let question = tf(
+                            "Replace the people, jobs, age groups and settings here with the ones from {}?",
+                            &[&data.name],
+                        );
+                        if !crate::web::confirm(&question) {
+                            return false;
+                        }
+
+                        let mut local_store =
+                            StorageService::new(Area::Local).expect("failed to get storage");
+                        store_roster(roster, &mut local_store);
+
+                        // every editor reads from storage when created, so start over
+                        crate::web::reload();
+                        false
+                    }
+                    Err(err) => {
+                        self.error = Some(tf("{} is not a chart export: {}", &[&data.name, &err]));
+                        true
+                    }
+                }
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.roster_version == props.roster_version {
+            return false;
+        }
+
+        self.roster_version = props.roster_version;
+        self.load();
+        true
+    }
+
+    fn view(&self) -> Html {
+        html! {
+            <div class="export">
+                <a href=self.href.clone() download=EXPORT_FILE>
+                    <i class=("fa", "fa-download") aria-hidden="true"></i>{ " " }{ t("Export") }
+                </a>
+                <label>
+                    <i class=("fa", "fa-upload") aria-hidden="true"></i>{ " " }{ t("Import") }{ " " }
+                    <input type="file" accept=".json,application/json" onchange=self.link.callback(ExportMsg::ImportFile) />
+                </label>
+                <label>{ t("Share") }{ " " }
+                    <input type="text" readonly=true value=self.share_link.clone() />
+                </label>
+                { self.error.as_ref().map_or_else(|| html! {}, |e| html! { <p class="error">{ e }</p> }) }
+            </div>
+        }
+    }
+}
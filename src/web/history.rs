@@ -0,0 +1,131 @@
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use yew::format::{Json, Text};
+use yew::services::StorageService;
+
+use crate::data::{History, PeopleOp, Person};
+use crate::web::people::PEOPLE_KEY;
+use crate::web::remote;
+use crate::web::sync::PeopleLogStore;
+use crate::web::workspace;
+
+pub(crate) const HISTORY_KEY: &str = "history_v1";
+/// Each change keeps two copies of a document, so only so many fit in local storage
+const HISTORY_LIMIT: usize = 50;
+
+/// A document in local storage before and after a change
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Change {
+    pub label: String,
+    key: String,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HistoryStore {
+    pub history: History<Change>,
+}
+
+impl HistoryStore {
+    pub fn restore(local_store: &mut StorageService) -> Self {
+        match local_store.restore(&workspace::key(HISTORY_KEY)) {
+            Json(Ok(history)) => history,
+            Json(Err(_)) => Self {
+                history: History::new(HISTORY_LIMIT),
+            },
+        }
+    }
+
+    pub fn store(&self, local_store: &mut StorageService) {
+        local_store.store(&workspace::key(HISTORY_KEY), Json(self));
+    }
+}
+
+fn read(local_store: &mut StorageService, key: &str) -> Option<String> {
+    let text: Text = local_store.restore(&workspace::key(key));
+    text.ok()
+}
+
+/// Runs `f`, which stores the document at `key`, and records the change so it can be undone
+pub fn tracked<F: FnOnce(&mut StorageService)>(
+    local_store: &mut StorageService,
+    label: String,
+    key: &str,
+    f: F,
+) {
+    let before = read(local_store, key);
+    f(local_store);
+    let after = read(local_store, key);
+    if before == after {
+        return;
+    }
+
+    debug!("recording change: {}", label);
+    let mut stored = HistoryStore::restore(local_store);
+    stored.history.push(Change {
+        label,
+        key: key.to_string(),
+        before,
+        after,
+    });
+    stored.store(local_store);
+}
+
+/// Puts a copy of a document back, as a newer version so everything that depends on it updates
+fn put_back(local_store: &mut StorageService, key: &str, text: Option<&str>) {
+    let mut document = match text.map(serde_json::from_str::<Value>) {
+        Some(Ok(document)) => document,
+        Some(Err(err)) => {
+            error!("could not read {} from history: {}", key, err);
+            return;
+        }
+        None => {
+            local_store.remove(&workspace::key(key));
+            return;
+        }
+    };
+
+    let inc = read(local_store, key)
+        .and_then(|t| serde_json::from_str::<remote::Versioned>(&t).ok())
+        .map_or(0, |v| v.inc);
+    document["inc"] = Value::from(inc + 1);
+    local_store.store(&workspace::key(key), Json(&document));
+
+    if key == PEOPLE_KEY {
+        if let Ok(people) = serde_json::from_value::<Vec<Person>>(document["people"].clone()) {
+            PeopleLogStore::record(local_store, Some(PeopleOp::Replace(people)));
+        }
+    } else {
+        remote::push_document(key);
+    }
+}
+
+/// Undoes the last change, returns false if there was nothing to undo
+pub fn undo(local_store: &mut StorageService) -> bool {
+    let mut stored = HistoryStore::restore(local_store);
+    let change = match stored.history.undo() {
+        Some(change) => change.clone(),
+        None => return false,
+    };
+
+    debug!("undoing: {}", change.label);
+    put_back(local_store, &change.key, change.before.as_deref());
+    stored.store(local_store);
+    true
+}
+
+/// Applies the last undone change again, returns false if there was nothing to redo
+pub fn redo(local_store: &mut StorageService) -> bool {
+    let mut stored = HistoryStore::restore(local_store);
+    let change = match stored.history.redo() {
+        Some(change) => change.clone(),
+        None => return false,
+    };
+
+    debug!("redoing: {}", change.label);
+    put_back(local_store, &change.key, change.after.as_deref());
+    stored.store(local_store);
+    true
+}
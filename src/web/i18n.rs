@@ -0,0 +1,427 @@
+use std::cell::Cell;
+use std::fmt::Display;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use yew::format::Json;
+use yew::services::{storage::Area, StorageService};
+
+use crate::data::{Ability, JobTemplate, Recurrence, Slot};
+
+const LANGUAGE_KEY: &str = "language_v1";
+
+/// The languages the page can be shown in, only kept in this browser
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Spanish];
+
+    /// The language's name in that language
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Español",
+        }
+    }
+
+    /// The stored language, or the browser's if it's one there's a catalog for
+    pub fn restore(local_store: &mut StorageService) -> Self {
+        match local_store.restore(LANGUAGE_KEY) {
+            Json(Ok(language)) => language,
+            Json(Err(_)) => Self::from_browser(),
+        }
+    }
+
+    pub fn store(self, local_store: &mut StorageService) {
+        debug!("saving language: {:?}", self);
+        local_store.store(LANGUAGE_KEY, Json(&self));
+    }
+
+    fn from_browser() -> Self {
+        let browser = web_sys::window()
+            .and_then(|w| w.navigator().language())
+            .unwrap_or_default();
+        if browser.starts_with("es") {
+            Language::Spanish
+        } else {
+            Language::English
+        }
+    }
+}
+
+thread_local! {
+    static LANGUAGE: Cell<Option<Language>> = const { Cell::new(None) };
+}
+
+/// The language everything is shown in, read once from storage, changing it reloads the page
+pub fn language() -> Language {
+    LANGUAGE.with(|language| match language.get() {
+        Some(language) => language,
+        None => {
+            let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+            let restored = Language::restore(&mut local_store);
+            language.set(Some(restored));
+            restored
+        }
+    })
+}
+
+/// Translates text for the page, the English text is the key to the catalog
+pub fn t(text: &'static str) -> &'static str {
+    translate(text).unwrap_or(text)
+}
+
+/// Translates text with `{}` where each of the args goes, in order
+pub fn tf(text: &'static str, args: &[&dyn Display]) -> String {
+    fill(t(text), args)
+}
+
+/// Puts each of the args in place of a `{}`, in order, a missing arg leaves the gap empty
+fn fill(text: &str, args: &[&dyn Display]) -> String {
+    let mut args = args.iter();
+    let mut formatted = String::new();
+    for (i, part) in text.split("{}").enumerate() {
+        if i > 0 {
+            if let Some(arg) = args.next() {
+                formatted.push_str(&arg.to_string());
+            }
+        }
+        formatted.push_str(part);
+    }
+    formatted
+}
+
+/// The built in groups are translated, ones added by name are shown as they were typed
+pub fn ability_name(ability: &Ability) -> &str {
+    translate(ability.to_str()).unwrap_or_else(|| ability.to_str())
+}
+
+/// The built in templates are translated, saved ones are shown as they were named
+pub fn template_name(template: &JobTemplate) -> &str {
+    translate(template.name()).unwrap_or_else(|| template.name())
+}
+
+fn translate(text: &str) -> Option<&'static str> {
+    match language() {
+        Language::English => None,
+        Language::Spanish => spanish(text),
+    }
+}
+
+/// The slot's group, translated, followed by its tags, e.g. "Adult +driver"
+pub fn slot(slot: &Slot) -> String {
+    std::iter::once(ability_name(slot.ability()).to_string())
+        .chain(slot.tags().iter().map(|t| format!("+{}", t)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A short weekday name
+pub fn weekday_name(weekday: Weekday) -> &'static str {
+    match (language(), weekday) {
+        (Language::English, Weekday::Mon) => "Mon",
+        (Language::English, Weekday::Tue) => "Tue",
+        (Language::English, Weekday::Wed) => "Wed",
+        (Language::English, Weekday::Thu) => "Thu",
+        (Language::English, Weekday::Fri) => "Fri",
+        (Language::English, Weekday::Sat) => "Sat",
+        (Language::English, Weekday::Sun) => "Sun",
+        (Language::Spanish, Weekday::Mon) => "lun",
+        (Language::Spanish, Weekday::Tue) => "mar",
+        (Language::Spanish, Weekday::Wed) => "mié",
+        (Language::Spanish, Weekday::Thu) => "jue",
+        (Language::Spanish, Weekday::Fri) => "vie",
+        (Language::Spanish, Weekday::Sat) => "sáb",
+        (Language::Spanish, Weekday::Sun) => "dom",
+    }
+}
+
+fn weekday_full(weekday: Weekday) -> &'static str {
+    match (language(), weekday) {
+        (Language::English, Weekday::Mon) => "Monday",
+        (Language::English, Weekday::Tue) => "Tuesday",
+        (Language::English, Weekday::Wed) => "Wednesday",
+        (Language::English, Weekday::Thu) => "Thursday",
+        (Language::English, Weekday::Fri) => "Friday",
+        (Language::English, Weekday::Sat) => "Saturday",
+        (Language::English, Weekday::Sun) => "Sunday",
+        (Language::Spanish, Weekday::Mon) => "lunes",
+        (Language::Spanish, Weekday::Tue) => "martes",
+        (Language::Spanish, Weekday::Wed) => "miércoles",
+        (Language::Spanish, Weekday::Thu) => "jueves",
+        (Language::Spanish, Weekday::Fri) => "viernes",
+        (Language::Spanish, Weekday::Sat) => "sábado",
+        (Language::Spanish, Weekday::Sun) => "domingo",
+    }
+}
+
+/// Month and day the way the language writes them, 7/4 in English and 4/7 in Spanish
+fn month_day(date: NaiveDate) -> String {
+    match language() {
+        Language::English => format!("{}/{}", date.month(), date.day()),
+        Language::Spanish => format!("{}/{}", date.day(), date.month()),
+    }
+}
+
+/// A day's name for the chart headers, e.g. "Sat 7/4"
+pub fn day_name(date: NaiveDate) -> String {
+    format!("{} {}", weekday_name(date.weekday()), month_day(date))
+}
+
+/// The weekday in full and the date, e.g. "Saturday 7/4"
+pub fn long_day_name(date: NaiveDate) -> String {
+    format!("{} {}", weekday_full(date.weekday()), month_day(date))
+}
+
+/// A date with the year, e.g. "Sat 7/4/2020"
+pub fn full_date(date: NaiveDate) -> String {
+    format!("{}/{}", day_name(date), date.year())
+}
+
+/// When a job is done, in words
+pub fn recurrence(recurrence: &Recurrence) -> String {
+    match recurrence {
+        Recurrence::Daily | Recurrence::EveryNDays(1) => t("Every day").to_string(),
+        Recurrence::Weekdays(days) if days.is_empty() => t("Never").to_string(),
+        Recurrence::Dates(dates) if dates.is_empty() => t("Never").to_string(),
+        Recurrence::Weekdays(days) => days
+            .iter()
+            .map(|d| weekday_name(*d))
+            .collect::<Vec<_>>()
+            .join(", "),
+        Recurrence::EveryNDays(n) => tf("Every {} days", &[n]),
+        Recurrence::Dates(dates) => dates
+            .iter()
+            .map(|d| month_day(*d))
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// The Spanish catalog
+fn spanish(text: &str) -> Option<&'static str> {
+    let translated = match text {
+        // the built in age groups
+        "Adult" => "Adulto",
+        "Teen" => "Adolescente",
+        "Child" => "Niño",
+
+        // the page
+        "Kitchen Patrol Charts" => "Turnos de cocina",
+        "Language" => "Idioma",
+        "A chart shared with you, it can't be changed here." => {
+            "Una tabla compartida contigo, aquí no se puede cambiar."
+        }
+        "Import into my chart" => "Importar a mi tabla",
+        "Open my chart" => "Abrir mi tabla",
+        "Couldn't open the shared chart, {}" => "No se pudo abrir la tabla compartida, {}",
+        "Undo" => "Deshacer",
+        "Redo" => "Rehacer",
+        "Undo {}" => "Deshacer {}",
+        "Redo {}" => "Rehacer {}",
+
+        // the chart
+        "Job Chart" => "Tabla de tareas",
+        "Starting {} for {} days" => "Desde el {} por {} días",
+        "Starting" => "Desde el",
+        "for" => "por",
+        "days" => "días",
+        "Highlight" => "Resaltar",
+        "no one" => "a nadie",
+        "Show" => "Mostrar",
+        "every age group" => "todos los grupos",
+        "Today only" => "Solo hoy",
+        "today isn't in this chart" => "hoy no está en esta tabla",
+        "Jobs by day" => "Tareas por día",
+        "Day cards" => "Tarjetas por día",
+        "People by day" => "Personas por día",
+        "Who does what" => "Quién hace qué",
+        "Jobs" => "Tareas",
+        "Scan to see the chart" => "Escanea para ver la tabla",
+        "This chart is too big for a QR code, share the link instead" => {
+            "Esta tabla es muy grande para un código QR, comparte el enlace"
+        }
+        "Swap {} and {}" => "Cambiar a {} y {}",
+        "Move {} to {}" => "Pasar a {} a {}",
+        "{} trades no longer fit the chart and were undone" => {
+            "{} cambios ya no caben en la tabla y se deshicieron"
+        }
+        "Change the start" => "Cambiar el comienzo",
+        "Change the number of days" => "Cambiar el número de días",
+        "Change the jobs on {}" => "Cambiar las tareas del {}",
+        "One-off job" => "Tarea única",
+        "Add the one-off job" => "Agregar la tarea única",
+        "Balance with past charts" => "Equilibrar con tablas pasadas",
+        "Stop balancing with past charts" => "Dejar de equilibrar con tablas pasadas",
+        "Archive this chart" => "Archivar esta tabla",
+        "Check off {}" => "Marcar a {} como hecho",
+        "Uncheck {}" => "Desmarcar a {}",
+        "{} is making up a missed job" => "{} recupera una tarea que no hizo",
+
+        // why the chart is the way it is
+        "Why these people?" => "¿Por qué estas personas?",
+        "Why these people for {} on {}" => "Por qué estas personas para {} el {}",
+        "Close" => "Cerrar",
+        "Each age group takes turns in line, whoever's first in line and can do the job gets it and goes to the back." => {
+            "Cada grupo de edad hace fila por turnos, quien va primero y puede hacer la tarea la recibe y pasa al final."
+        }
+        "first in line of those who can do it" => "primero en la fila de quienes pueden hacerla",
+        "doesn't have {}" => "no tiene {}",
+        "later in line" => "más atrás en la fila",
+        "{}, {} jobs so far in the chart: {}" => "{}, {} tareas hasta ahora en la tabla: {}",
+        "{}: nobody in the group can do it" => "{}: nadie del grupo puede hacerla",
+        "Only the {} group can fill this slot, {} people in other groups weren't considered." => {
+            "Solo el grupo {} puede ocupar este lugar, no se consideró a {} personas de otros grupos."
+        }
+        "After the day before the {} line was moved on {} places without a job, so the big jobs don't always fall on the same people." => {
+            "Después del día anterior la fila de {} avanzó {} lugares sin tarea, para que las tareas grandes no siempre les toquen a los mismos."
+        }
+        "{} was put in to make up a missed job." => "Se puso a {} para recuperar una tarea que no hizo.",
+        "{} has it now, it was traded after the chart was made." => {
+            "Ahora la tiene {}, se cambió después de hacer la tabla."
+        }
+
+        // past charts
+        "Past charts" => "Tablas pasadas",
+        "Charts are archived here once they're over, or with Archive this chart." => {
+            "Las tablas se archivan aquí cuando terminan, o con Archivar esta tabla."
+        }
+        "Who did what" => "Quién hizo qué",
+        "Charts" => "Tablas",
+        "Done" => "Hechas",
+        "Per chart" => "Por tabla",
+
+        // people
+        "All the beautiful people" => "Toda la gente bonita",
+        "Person" => "Persona",
+        "Ability" => "Grupo",
+        "Skills" => "Habilidades",
+        "Unsaved changes" => "Cambios sin guardar",
+        "There are unsaved changes to people" => "Hay cambios sin guardar en las personas",
+        "Fix the marked people before saving" => "Corrige las personas marcadas antes de guardar",
+        "Someone else changed the people on the server while these edits were waiting." => {
+            "Alguien más cambió las personas en el servidor mientras estos cambios esperaban."
+        }
+        "Keep mine" => "Quedarme con los míos",
+        "Use theirs" => "Usar los suyos",
+        "{} saved edits waiting for the server" => "{} cambios guardados esperando al servidor",
+        "Save people" => "Guardar personas",
+        "Save {}" => "Guardar {}",
+        "Delete {}" => "Borrar {}",
+        "Edit {}" => "Editar {}",
+        "Cancel editing {}" => "Dejar de editar {}",
+        "Add a person" => "Agregar una persona",
+        "Save everyone" => "Guardar a todos",
+        "Add a job" => "Agregar una tarea",
+        "Save all jobs" => "Guardar todas las tareas",
+        "Add someone to the job" => "Agregar a alguien a la tarea",
+        "Remove from the job" => "Quitar de la tarea",
+
+        // jobs
+        "All the jobs to do" => "Todas las tareas",
+        "Job" => "Tarea",
+        "Needs" => "Necesita",
+        "When" => "Cuándo",
+        "Save jobs" => "Guardar tareas",
+        "Every day" => "Todos los días",
+        "Weekdays" => "Días de la semana",
+        "Every N days" => "Cada N días",
+        "Dates" => "Fechas",
+        "Every {} days" => "Cada {} días",
+        "required skills" => "habilidades necesarias",
+        "Never" => "Nunca",
+
+        // templates
+        "Family camp" => "Campamento familiar",
+        "Ski cabin" => "Cabaña de esquí",
+        "Holiday dinner" => "Cena de fiesta",
+        "Small household chores" => "Tareas de la casa",
+        "Template" => "Plantilla",
+        "{} (saved)" => "{} (guardada)",
+        "Replace the jobs" => "Reemplazar las tareas",
+        "Add to the jobs" => "Agregar a las tareas",
+        "Delete template" => "Borrar plantilla",
+        "Template name" => "Nombre de la plantilla",
+        "Save the jobs as a template" => "Guardar las tareas como plantilla",
+        "Use the {} jobs" => "Usar las tareas de {}",
+        "Add the {} jobs" => "Agregar las tareas de {}",
+
+        // age groups
+        "Age groups" => "Grupos de edad",
+        "Group" => "Grupo",
+        "New group" => "Nuevo grupo",
+        "Add group" => "Agregar grupo",
+        "Move {} up" => "Subir {}",
+        "Save age groups" => "Guardar grupos de edad",
+
+        // validation
+        "a name is needed" => "falta el nombre",
+        "the name is already used" => "el nombre ya existe",
+        "the age group no longer exists" => "el grupo de edad ya no existe",
+        "at least one person is needed" => "se necesita al menos una persona",
+        "it has to be every 1 day or more" => "tiene que ser cada 1 día o más",
+
+        // charts
+        "My chart" => "Mi tabla",
+        "Chart" => "Tabla",
+        "Chart name" => "Nombre de la tabla",
+        "Thanksgiving" => "Día de Acción de Gracias",
+        "New chart" => "Nueva tabla",
+        "Rename" => "Renombrar",
+        "Chart to copy from" => "Tabla de la que copiar",
+        "Copy its roster here" => "Copiar su lista aquí",
+        "Delete the {} chart, with its people, jobs, past charts and history?" => {
+            "¿Borrar la tabla {}, con sus personas, tareas, tablas pasadas e historial?"
+        }
+        "Delete the chart for {}? Who did what will no longer count it." => {
+            "¿Eliminar la tabla de {}? Quién hizo qué ya no la contará."
+        }
+        "Replace my people, jobs, age groups, settings and chart with the shared ones?" => {
+            "¿Reemplazar mis personas, tareas, grupos de edad, ajustes y tabla con los compartidos?"
+        }
+        "Replace the people, jobs, age groups and settings here with the ones from {}?" => {
+            "¿Reemplazar las personas, tareas, grupos de edad y ajustes de aquí con los de {}?"
+        }
+
+        // export and sharing
+        "Export" => "Exportar",
+        "Import" => "Importar",
+        "Share" => "Compartir",
+        "{} is not a chart export: {}" => "{} no es una tabla exportada: {}",
+        "Saved in this browser only" => "Guardado solo en este navegador",
+        "{} changed here and on the server." => "{} cambiaron aquí y en el servidor.",
+        "The jobs" => "Las tareas",
+        "The age groups" => "Los grupos de edad",
+        "The chart's dates" => "Las fechas de la tabla",
+        "The chart" => "La tabla",
+        "Shared through {}" => "Compartido a través de {}",
+        _ => return None,
+    };
+
+    Some(translated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_each_gap_in_order() {
+        assert_eq!(
+            fill("Swap {} and {}", &[&"Ann", &"Bob"]),
+            "Swap Ann and Bob"
+        );
+        assert_eq!(
+            fill("{} is making up a missed job", &[&"Ann"]),
+            "Ann is making up a missed job"
+        );
+        assert_eq!(fill("{} of {} on {}", &[&1, &3]), "1 of 3 on ");
+        assert_eq!(fill("No gaps", &[&"Ann"]), "No gaps");
+    }
+}
@@ -0,0 +1,663 @@
+use chrono::Weekday;
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use yew::callback::Callback;
+use yew::format::Json;
+use yew::prelude::*;
+use yew::services::{storage::Area, StorageService};
+
+use crate::data::*;
+use crate::web::abilities::AbilitiesStore;
+use crate::web::edit_delete::EditDelete;
+use crate::web::history;
+use crate::web::i18n::{self, t, tf};
+use crate::web::remote;
+use crate::web::workspace;
+use crate::web::{Id, IsEditting};
+
+pub(crate) const JOBS_KEY: &str = "jobs_v1";
+/// Saved templates are kept for every chart in this browser
+const TEMPLATES_KEY: &str = "job_templates_v1";
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+type SlotId = usize;
+
+pub enum JobsMsg {
+    AddJob,
+    SaveJobs,
+    EditJob(Id),
+    DeleteJob(Id),
+    JobNameInput(Id, String),
+    AddSlot(Id),
+    DeleteSlot(Id, SlotId),
+    SlotAbilityInput(Id, SlotId, Ability),
+    SlotTagsInput(Id, SlotId, Tags),
+    RecurrenceInput(Id, Recurrence),
+    TemplateInput(usize),
+    ReplaceJobs,
+    ExtendJobs,
+    TemplateNameInput(String),
+    SaveTemplate,
+    DeleteTemplate,
+}
+
+#[derive(Clone)]
+pub struct JobsModel {
+    inc: usize,
+    /// The stored jobs, with any unsaved edits, followed by the jobs added since the last save
+    jobs: Vec<(Job, IsEditting)>,
+    history_version: usize,
+    abilities_version: usize,
+    abilities: Abilities,
+    templates: TemplatesStore,
+    /// The built in templates followed by the saved ones
+    template: usize,
+    template_name: String,
+    on_save: Option<Callback<usize>>,
+    link: ComponentLink<Self>,
+}
+
+#[derive(Clone, Default, PartialEq, Properties)]
+pub struct JobsProps {
+    pub abilities_version: usize,
+    /// Changes when an undo or redo replaced what's stored
+    pub history_version: usize,
+    pub on_save: Option<Callback<usize>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct JobsStore {
+    pub inc: usize,
+    pub jobs: Vec<Job>,
+}
+
+impl JobsStore {
+    pub fn restore(local_store: &mut StorageService) -> Option<Self> {
+        let from_store = local_store.restore(&workspace::key(JOBS_KEY));
+        match from_store {
+            Json(Ok(jobs)) => Some(jobs),
+            Json(Err(err)) => {
+                error!("could not load from local store: {}", err);
+                None
+            }
+        }
+    }
+
+    pub fn store(&mut self, local_store: &mut StorageService) {
+        self.inc += 1;
+        debug!("saving jobs: {}", self.inc);
+        local_store.store(&workspace::key(JOBS_KEY), Json(self as &Self));
+        remote::push("jobs");
+    }
+}
+
+/// The job lists saved as templates
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct TemplatesStore {
+    pub templates: Vec<JobTemplate>,
+}
+
+impl TemplatesStore {
+    pub fn restore(local_store: &mut StorageService) -> Self {
+        match local_store.restore(TEMPLATES_KEY) {
+            Json(Ok(templates)) => templates,
+            Json(Err(_)) => Self::default(),
+        }
+    }
+
+    pub fn store(&self, local_store: &mut StorageService) {
+        debug!("saving templates: {}", self.templates.len());
+        local_store.store(TEMPLATES_KEY, Json(self));
+    }
+
+    /// Adds the template, replacing a saved one of the same name
+    fn save(&mut self, template: JobTemplate) {
+        match self
+            .templates
+            .iter_mut()
+            .find(|t| t.name() == template.name())
+        {
+            Some(saved) => *saved = template,
+            None => self.templates.push(template),
+        }
+    }
+}
+
+impl From<JobsModel> for JobsStore {
+    fn from(model: JobsModel) -> Self {
+        Self {
+            inc: model.inc,
+            jobs: model.jobs.into_iter().map(|(j, _)| j).collect(),
+        }
+    }
+}
+
+impl JobsModel {
+    fn from(model: JobsStore, props: JobsProps, link: ComponentLink<Self>) -> Self {
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        let (abilities_version, abilities) = AbilitiesStore::restore_or_default(&mut local_store);
+
+        Self {
+            inc: model.inc,
+            jobs: model.jobs.into_iter().map(|j| (j, false)).collect(),
+            history_version: props.history_version,
+            abilities_version,
+            abilities,
+            templates: TemplatesStore::restore(&mut local_store),
+            template: 0,
+            template_name: String::new(),
+            on_save: props.on_save,
+            link,
+        }
+    }
+
+    /// The built in templates and then the saved ones, the index is `template`
+    fn all_templates(&self) -> Vec<JobTemplate> {
+        let mut templates = crate::builtin_templates();
+        templates.extend(self.templates.templates.iter().cloned());
+        templates
+    }
+
+    /// Applies the chosen template to the jobs and saves them, so it can be undone
+    fn apply_template<F: FnOnce(&JobTemplate, &mut Vec<Job>)>(
+        &mut self,
+        label: &'static str,
+        f: F,
+    ) -> bool {
+        let template = match self.all_templates().into_iter().nth(self.template) {
+            Some(template) => template,
+            None => return false,
+        };
+
+        debug!("applying template: {}", template.name());
+        let mut jobs = self.jobs.drain(..).map(|j| j.0).collect::<Vec<_>>();
+        f(&template, &mut jobs);
+        self.jobs = jobs.into_iter().map(|j| (j, false)).collect();
+        self.save(tf(label, &[&i18n::template_name(&template)]));
+        true
+    }
+
+    fn save(&mut self, label: String) {
+        debug!("saving JobsModel");
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        for (job, _) in self.jobs.iter_mut() {
+            let name = job.name().trim().to_string();
+            job.set_name(name);
+        }
+        let mut jobs: JobsStore = self.clone().into();
+        history::tracked(&mut local_store, label, JOBS_KEY, |local_store| {
+            jobs.store(local_store)
+        });
+        self.inc = jobs.inc;
+        for job in self.jobs.iter_mut() {
+            job.1 = false;
+        }
+
+        if let Some(e) = self.on_save.as_ref() {
+            e.emit(self.inc)
+        }
+    }
+
+    /// Replaces the jobs with the stored ones, e.g. after an undo
+    fn reload(&mut self, local_store: &mut StorageService) {
+        if let Some(stored) = JobsStore::restore(local_store) {
+            self.inc = stored.inc;
+            self.jobs = stored.jobs.into_iter().map(|j| (j, false)).collect();
+
+            if let Some(e) = self.on_save.as_ref() {
+                e.emit(self.inc)
+            }
+        }
+    }
+
+    /// The problems with each job, as they are in the editor
+    fn errors(&self) -> Vec<JobErrors> {
+        let jobs: Vec<Job> = self.jobs.iter().map(|j| j.0.clone()).collect();
+        validate_jobs(&jobs)
+    }
+
+    fn default_slot(&self) -> Slot {
+        Slot::from(self.abilities.first().cloned().unwrap_or_default())
+    }
+
+    /// Applies f to the slot if the job is being editted, returns true if anything changed
+    fn update_slot<F: FnOnce(&mut Slot) -> bool>(&mut self, id: Id, slot: SlotId, f: F) -> bool {
+        self.jobs
+            .get_mut(id)
+            .filter(|j| j.1)
+            .and_then(|j| j.0.people_mut().get_mut(slot))
+            .map(f)
+            .unwrap_or(false)
+    }
+
+    fn view_recurrence(&self, id: Id, recurrence: &Recurrence, is_editting: IsEditting) -> Html {
+        if !is_editting {
+            return html! { <>{ i18n::recurrence(recurrence) }</> };
+        }
+
+        let kinds = Recurrence::kinds();
+        let select_kind = |kind: &Recurrence| {
+            html! {
+                <option selected={kind.kind() == recurrence.kind()}>{ t(kind.kind()) }</option>
+            }
+        };
+        let details = match recurrence {
+            Recurrence::Daily => html! {},
+            Recurrence::Weekdays(days) => {
+                let weekday = |day: Weekday| {
+                    let mut toggled = days.clone();
+                    match toggled.iter().position(|d| *d == day) {
+                        Some(idx) => {
+                            toggled.remove(idx);
+                        }
+                        None => toggled.push(day),
+                    }
+                    toggled.sort_by_key(|d| d.num_days_from_monday());
+
+                    html! {
+                        <label>
+                            <input type="checkbox" checked={days.contains(&day)} onclick=self.link.callback(move |_| JobsMsg::RecurrenceInput(id, Recurrence::Weekdays(toggled.clone()))) />
+                            { i18n::weekday_name(day) }
+                        </label>
+                    }
+                };
+
+                html! { <div>{ for WEEKDAYS.iter().map(|d| weekday(*d)) }</div> }
+            }
+            Recurrence::EveryNDays(n) => html! {
+                <input type="number" min="1" value={n.to_string()} onchange=self.link.callback(move |e| match e {
+                    ChangeData::Value(v) => JobsMsg::RecurrenceInput(id, Recurrence::EveryNDays(v.trim().parse().unwrap_or(0))),
+                    _ => unreachable!(),
+                }) />
+            },
+            Recurrence::Dates(dates) => {
+                let dates = dates
+                    .iter()
+                    .map(|d| d.format("%Y-%m-%d").to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                html! {
+                    <input type="text" placeholder="2020-07-01, 2020-07-04" value={dates} onchange=self.link.callback(move |e| match e {
+                        ChangeData::Value(v) => JobsMsg::RecurrenceInput(id, Recurrence::Dates(parse_dates(&v))),
+                        _ => unreachable!(),
+                    }) />
+                }
+            }
+        };
+
+        html! {
+            <>
+                <select onchange=self.link.callback(move |e| match e {
+                    ChangeData::Select(se) => JobsMsg::RecurrenceInput(id, Recurrence::kinds().into_iter().nth(se.selected_index() as usize).unwrap_or_default()),
+                    _ => unreachable!(),
+                })>
+                    { for kinds.iter().map(select_kind) }
+                </select>
+                { details }
+            </>
+        }
+    }
+
+    fn view_templates(&self, is_valid: bool) -> Html {
+        let builtin = crate::builtin_templates().len();
+        let select_template = |(i, template): (usize, JobTemplate)| {
+            let name = if i < builtin {
+                i18n::template_name(&template).to_string()
+            } else {
+                tf("{} (saved)", &[&template.name()])
+            };
+            html! {
+                <option value={i.to_string()} selected={i == self.template}>{ name }</option>
+            }
+        };
+        let delete = if self.template >= builtin {
+            html! {
+                <button type="button" onclick=self.link.callback(|_| JobsMsg::DeleteTemplate)>{ t("Delete template") }</button>
+            }
+        } else {
+            html! {}
+        };
+
+        html! {
+            <div class="templates">
+                <div>
+                    <label>{ t("Template") }{" "}
+                        <select onchange=self.link.callback(|e| match e {
+                            ChangeData::Select(se) => JobsMsg::TemplateInput(se.value().parse().unwrap_or_default()),
+                            _ => unreachable!(),
+                        })>
+                            { for self.all_templates().into_iter().enumerate().map(select_template) }
+                        </select>
+                    </label>
+                    <button type="button" onclick=self.link.callback(|_| JobsMsg::ReplaceJobs)>{ t("Replace the jobs") }</button>
+                    <button type="button" disabled=!is_valid onclick=self.link.callback(|_| JobsMsg::ExtendJobs)>{ t("Add to the jobs") }</button>
+                    { delete }
+                </div>
+                <div>
+                    <input type="text" aria-label=t("Template name") placeholder=t("Template name") value={&self.template_name} oninput=self.link.callback(|e: InputData| JobsMsg::TemplateNameInput(e.value)) />
+                    <button type="button" disabled=!is_valid onclick=self.link.callback(|_| JobsMsg::SaveTemplate)>{ t("Save the jobs as a template") }</button>
+                </div>
+            </div>
+        }
+    }
+
+    fn view_slot(&self, id: Id, slot_id: SlotId, slot: &Slot, is_editting: IsEditting) -> Html {
+        if !is_editting {
+            return html! {
+                <li>{ i18n::slot(slot) }</li>
+            };
+        }
+
+        let select_ability = |ability: &Ability| {
+            html! {
+                <option value={ability.to_str()} selected={slot.ability() == ability}>{ i18n::ability_name(ability) }</option>
+            }
+        };
+        // keep showing a group that has since been removed from the list
+        let removed = if self.abilities.contains(slot.ability()) {
+            None
+        } else {
+            Some(slot.ability())
+        };
+
+        html! {
+            <li>
+                <select onchange=self.link.callback(move |e| match e {
+                    ChangeData::Select(se) => JobsMsg::SlotAbilityInput(id, slot_id, Ability::new(se.value())),
+                    _ => unreachable!(),
+                })>
+                    { for self.abilities.iter().chain(removed).map(select_ability) }
+                </select>
+                <input type="text" placeholder=t("required skills") value={tags_to_string(slot.tags())} onchange=self.link.callback(move |e| match e {
+                    ChangeData::Value(v) => JobsMsg::SlotTagsInput(id, slot_id, parse_tags(&v)),
+                    _ => unreachable!(),
+                }) />
+                <button type="button" class="icon" aria-label=t("Remove from the job") title=t("Remove from the job") onclick=self.link.callback(move |_| JobsMsg::DeleteSlot(id, slot_id))>
+                    <i class=("fa", "fa-minus-square-o", "fa-fw") aria-hidden="true"></i>
+                </button>
+            </li>
+        }
+    }
+}
+
+impl Component for JobsModel {
+    type Message = JobsMsg;
+    type Properties = JobsProps;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        debug!("creating JobsModel");
+
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+
+        match JobsStore::restore(&mut local_store) {
+            Some(this) => Self::from(this, props, link),
+            None => {
+                let mut jobs = JobsStore {
+                    inc: 0,
+                    jobs: crate::default_jobs(),
+                };
+
+                jobs.store(&mut local_store);
+                Self::from(jobs, props, link)
+            }
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            JobsMsg::SaveJobs if !self.errors().iter().all(JobErrors::is_valid) => {
+                debug!("not saving jobs with errors");
+                true
+            }
+            JobsMsg::SaveJobs => {
+                self.save(t("Save jobs").to_string());
+                true
+            }
+            JobsMsg::AddJob => {
+                debug!("adding a Job");
+                let job = Job::new("New job", vec![self.default_slot()]);
+                self.jobs.push((job, true));
+                true
+            }
+            JobsMsg::EditJob(id) => {
+                debug!("edit job: {}", id);
+                self.jobs
+                    .get_mut(id)
+                    .map(|j| !std::mem::replace(&mut j.1, true))
+                    .unwrap_or(false)
+            }
+            JobsMsg::DeleteJob(id) => {
+                if self.jobs.get(id).is_none() {
+                    return false;
+                }
+                let job = self.jobs.remove(id);
+                debug!("deleted {:?}", job);
+
+                // only the stored job is removed, so the other jobs' edits stay unsaved
+                let mut local_store =
+                    StorageService::new(Area::Local).expect("failed to get storage");
+                let mut stored = match JobsStore::restore(&mut local_store) {
+                    Some(stored) if id < stored.jobs.len() => stored,
+                    // a job added since the last save is only in the editor
+                    _ => return true,
+                };
+                let job = stored.jobs.remove(id);
+
+                // saved right away so that it can be undone
+                let label = tf("Delete {}", &[&job.name()]);
+                history::tracked(&mut local_store, label, JOBS_KEY, |local_store| {
+                    stored.store(local_store)
+                });
+                self.inc = stored.inc;
+                if let Some(e) = self.on_save.as_ref() {
+                    e.emit(self.inc)
+                }
+                true
+            }
+            JobsMsg::JobNameInput(id, name) => self
+                .jobs
+                .get_mut(id)
+                .map(|j| {
+                    if j.0.name() != name {
+                        j.0.set_name(name);
+                        true
+                    } else {
+                        false
+                    }
+                })
+                .unwrap_or(false),
+            JobsMsg::AddSlot(id) => {
+                let slot = self.default_slot();
+                self.jobs
+                    .get_mut(id)
+                    .map(|j| {
+                        j.0.people_mut().push(slot);
+                        true
+                    })
+                    .unwrap_or(false)
+            }
+            JobsMsg::DeleteSlot(id, slot) => self
+                .jobs
+                .get_mut(id)
+                .filter(|j| slot < j.0.people().len())
+                .map(|j| {
+                    j.0.people_mut().remove(slot);
+                    true
+                })
+                .unwrap_or(false),
+            JobsMsg::SlotAbilityInput(id, slot, ability) => self.update_slot(id, slot, |s| {
+                if *s.ability() != ability {
+                    s.set_ability(ability);
+                    true
+                } else {
+                    false
+                }
+            }),
+            JobsMsg::RecurrenceInput(id, recurrence) => self
+                .jobs
+                .get_mut(id)
+                .filter(|j| j.1 && *j.0.recurrence() != recurrence)
+                .map(|j| {
+                    debug!("saving recurrence: {}", recurrence);
+                    j.0.set_recurrence(recurrence);
+                    true
+                })
+                .unwrap_or(false),
+            JobsMsg::SlotTagsInput(id, slot, tags) => self.update_slot(id, slot, |s| {
+                if *s.tags() != tags {
+                    s.set_tags(tags);
+                    true
+                } else {
+                    false
+                }
+            }),
+            JobsMsg::TemplateInput(template) => {
+                self.template = template;
+                false
+            }
+            JobsMsg::ReplaceJobs => {
+                self.apply_template("Use the {} jobs", |template, jobs| template.replace(jobs))
+            }
+            // the edited jobs are saved along with the added ones, so they need to be valid
+            JobsMsg::ExtendJobs if !self.errors().iter().all(JobErrors::is_valid) => true,
+            JobsMsg::ExtendJobs => {
+                self.apply_template("Add the {} jobs", |template, jobs| template.extend(jobs))
+            }
+            JobsMsg::TemplateNameInput(name) => {
+                self.template_name = name;
+                false
+            }
+            JobsMsg::SaveTemplate => {
+                let name = self.template_name.trim().to_string();
+                if name.is_empty() || !self.errors().iter().all(JobErrors::is_valid) {
+                    return false;
+                }
+
+                debug!("saving template: {}", name);
+                let jobs = self.jobs.iter().map(|j| j.0.clone()).collect();
+                self.templates.save(JobTemplate::new(name, jobs));
+                let mut local_store =
+                    StorageService::new(Area::Local).expect("failed to get storage");
+                self.templates.store(&mut local_store);
+                self.template_name.clear();
+                true
+            }
+            JobsMsg::DeleteTemplate => {
+                // only saved templates can be removed, they come after the built in ones
+                let saved = match self.template.checked_sub(crate::builtin_templates().len()) {
+                    Some(saved) if saved < self.templates.templates.len() => saved,
+                    _ => return false,
+                };
+
+                let template = self.templates.templates.remove(saved);
+                debug!("deleting template: {}", template.name());
+                let mut local_store =
+                    StorageService::new(Area::Local).expect("failed to get storage");
+                self.templates.store(&mut local_store);
+                self.template = 0;
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let mut render = false;
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        if self.history_version != props.history_version {
+            debug!("reloading JobsModel");
+            self.history_version = props.history_version;
+            self.reload(&mut local_store);
+            render = true;
+        }
+
+        if self.abilities_version != props.abilities_version {
+            debug!("updating JobsModel abilities");
+            let (abilities_version, abilities) =
+                AbilitiesStore::restore_or_default(&mut local_store);
+            self.abilities_version = abilities_version;
+            self.abilities = abilities;
+            render = true;
+        }
+        render
+    }
+
+    fn view(&self) -> Html {
+        let errors = self.errors();
+        let is_valid = errors.iter().all(JobErrors::is_valid);
+        let view_error = |error: Option<Invalid>| match error {
+            Some(error) => html! { <div class="field_error error">{ t(error.message()) }</div> },
+            None => html! {},
+        };
+        let job_row = |id: Id, job: &(Job, IsEditting)| {
+            let (job, is_editting) = (&job.0, job.1);
+            let error = errors.get(id).copied().unwrap_or_default();
+            let name = if is_editting {
+                html! {
+                    <input type="text" value={job.name()} oninput=self.link.callback(move |e: InputData| JobsMsg::JobNameInput(id, e.value)) />
+                }
+            } else {
+                html! { <>{ job.name() }</> }
+            };
+            let add_slot = if is_editting {
+                html! {
+                    <button type="button" class="icon" aria-label=t("Add someone to the job") title=t("Add someone to the job") onclick=self.link.callback(move |_| JobsMsg::AddSlot(id))>
+                        <i class=("fa", "fa-plus-square-o", "fa-fw") aria-hidden="true"></i>
+                    </button>
+                }
+            } else {
+                html! {}
+            };
+
+            html! {
+                <tr>
+                    <td>{ name }{ view_error(error.name) }</td>
+                    <td>
+                        <ul class="slots">
+                            { for job.people().iter().enumerate().map(|(s, slot)| self.view_slot(id, s, slot, is_editting)) }
+                        </ul>
+                        { add_slot }
+                        { view_error(error.slots) }
+                    </td>
+                    <td>{ self.view_recurrence(id, job.recurrence(), is_editting) }{ view_error(error.recurrence) }</td>
+                    <td class="edit_delete">
+                        <EditDelete id=id label=job.name().to_string() is_editting=is_editting on_edit=self.link.callback(JobsMsg::EditJob) on_delete=self.link.callback(JobsMsg::DeleteJob) />
+                    </td>
+                </tr>
+            }
+        };
+
+        html! {
+            <>
+                <h2>{ t("All the jobs to do") }</h2>
+                <table>
+                    <thead>
+                        <tr><th>{ t("Job") }</th><th>{ t("Needs") }</th><th>{ t("When") }</th><th>{" "}</th></tr>
+                    </thead>
+                    <tbody>
+                        { for self.jobs.iter().enumerate().map(|(i, j)| job_row(i, j)) }
+                    </tbody>
+                    <tfoot>
+                        <tr><td>
+                            <button type="button" onclick=self.link.callback(|_| JobsMsg::AddJob)>
+                                <i class=("fa", "fa-plus-square") aria-hidden="true"></i>
+                                { " " }{ t("Add a job") }
+                            </button>
+                            <button type="button" disabled=!is_valid onclick=self.link.callback(|_| JobsMsg::SaveJobs)>
+                                <i class=("fa", "fa-floppy-o") aria-hidden="true"></i>
+                                { " " }{ t("Save all jobs") }
+                            </button>
+                        </td></tr>
+                    </tfoot>
+                </table>
+                { self.view_templates(is_valid) }
+            </>
+        }
+    }
+}
@@ -1,7 +1,69 @@
+mod abilities;
+mod archive;
 mod chart;
+mod day_editor;
+mod edit_delete;
+mod export;
+mod history;
+mod i18n;
+mod jobs;
 mod people;
+mod qr;
+mod remote;
 mod root;
+mod settings;
+mod share;
+mod sync;
+mod workspace;
 
+pub use self::abilities::AbilitiesModel;
+pub use self::archive::ArchiveModel;
 pub use self::chart::Chart;
+pub use self::export::ExportModel;
+pub use self::jobs::JobsModel;
 pub use self::people::PeopleModel;
+pub use self::remote::ServerModel;
 pub use self::root::RootModel;
+
+type IsEditting = bool;
+type Id = usize;
+
+/// Moves the keyboard focus to the element, if it's been drawn
+///
+/// With `if_lost` focus is only moved when nothing has it, e.g. after the focused input was removed.
+fn focus(node: &yew::NodeRef, if_lost: bool) {
+    if if_lost {
+        let document = web_sys::window().and_then(|w| w.document());
+        let active = document.as_ref().and_then(|d| d.active_element());
+        let body = document.as_ref().and_then(|d| d.body());
+        let is_lost = match active {
+            Some(active) => body.is_some_and(|b| active.is_same_node(Some(b.as_ref()))),
+            None => true,
+        };
+        if !is_lost {
+            return;
+        }
+    }
+
+    if let Some(element) = node.cast::<web_sys::HtmlElement>() {
+        if let Err(err) = element.focus() {
+            log::error!("failed to focus: {:?}", err);
+        }
+    }
+}
+
+/// Asks the user to confirm something that can't be undone, false if they cancel
+fn confirm(message: &str) -> bool {
+    web_sys::window()
+        .and_then(|w| w.confirm_with_message(message).ok())
+        .unwrap_or(false)
+}
+
+/// Reloads the page, for when storage was replaced underneath the editors
+fn reload() {
+    if let Some(window) = web_sys::window() {
+        if let Err(err) = window.location().reload() {
+            log::error!("failed to reload: {:?}", err);
+        }
+    }
+}
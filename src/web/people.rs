@@ -1,36 +1,89 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
 use log::{debug, error};
 use serde::{Deserialize, Serialize};
-use web_sys::HtmlSelectElement;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{BeforeUnloadEvent, HtmlElement, HtmlSelectElement, KeyboardEvent};
 use yew::callback::Callback;
 use yew::format::Json;
 use yew::prelude::*;
+use yew::services::timeout::{TimeoutService, TimeoutTask};
 use yew::services::{storage::Area, StorageService};
 
 use crate::data::*;
+use crate::web::abilities::AbilitiesStore;
+use crate::web::edit_delete::EditDelete;
+use crate::web::history;
+use crate::web::i18n::{self, t, tf};
+use crate::web::remote;
+use crate::web::sync::{self, PeopleLogStore, SyncStatus};
+use crate::web::workspace;
+use crate::web::{Id, IsEditting};
 
-const PEOPLE_KEY: &str = "people_v1";
-type IsEditting = bool;
-type Id = usize;
+pub(crate) const PEOPLE_KEY: &str = "people_v1";
 
 pub enum PeopleMsg {
     AddPerson,
     SavePeople,
     EditPerson(Id),
+    SavePerson(Id),
+    CancelPerson(Id),
     DeletePerson(Id),
     PersonNameInput(Id, String),
     PersonAbilityInput(Id, Ability),
+    PersonTagsInput(Id, Tags),
+    Sync,
+    Synced(SyncStatus),
+    ResolveConflict(bool),
+}
+
+/// A person in the editor along with the stored copy, to tell if there are unsaved changes
+#[derive(Clone)]
+struct PersonRow {
+    person: Person,
+    /// None until the person is first saved
+    saved: Option<Person>,
+    is_editting: IsEditting,
+}
+
+impl PersonRow {
+    fn saved(person: Person) -> Self {
+        Self {
+            saved: Some(person.clone()),
+            person,
+            is_editting: false,
+        }
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.saved.as_ref() != Some(&self.person)
+    }
 }
 
 #[derive(Clone)]
 pub struct PeopleModel {
     inc: usize,
-    people: Vec<(Person, IsEditting)>,
+    people: Vec<PersonRow>,
+    /// Shared with the beforeunload listener, true while anything is unsaved
+    unsaved: Rc<Cell<bool>>,
+    /// Saved edits the server doesn't have yet
+    pending: usize,
+    sync: Option<SyncStatus>,
+    history_version: usize,
+    abilities_version: usize,
+    abilities: Abilities,
     on_save: Option<Callback<usize>>,
     link: ComponentLink<Self>,
 }
 
 #[derive(Clone, Default, PartialEq, Properties)]
 pub struct PeopleProps {
+    pub abilities_version: usize,
+    /// Changes when an undo or redo replaced what's stored
+    pub history_version: usize,
     pub on_save: Option<Callback<usize>>,
 }
 
@@ -42,7 +95,7 @@ pub struct PeopleStore {
 
 impl PeopleStore {
     pub fn restore(local_store: &mut StorageService) -> Option<Self> {
-        let from_store = local_store.restore(PEOPLE_KEY);
+        let from_store = local_store.restore(&workspace::key(PEOPLE_KEY));
         match from_store {
             Json(Ok(people)) => Some(people),
             // TODO: reset local store...
@@ -56,30 +109,200 @@ impl PeopleStore {
     pub fn store(&mut self, local_store: &mut StorageService) {
         self.inc += 1;
         debug!("saving people: {}", self.inc);
-        local_store.store(PEOPLE_KEY, Json(self as &Self));
+        local_store.store(&workspace::key(PEOPLE_KEY), Json(self as &Self));
     }
 }
 
-impl From<PeopleModel> for PeopleStore {
-    fn from(model: PeopleModel) -> Self {
-        Self {
-            inc: model.inc,
-            people: model.people.into_iter().map(|(p, _)| p).collect(),
+/// The text field or select the key was pressed in, if it was in one
+fn key_field(e: &KeyboardEvent) -> Option<HtmlElement> {
+    e.target()
+        .and_then(|t| t.dyn_into::<HtmlElement>().ok())
+        .filter(|e| matches!(e.tag_name().as_str(), "INPUT" | "SELECT"))
+}
+
+/// Enter saves the row being edited and Escape cancels it
+fn row_keys(id: Id, e: KeyboardEvent) -> Vec<PeopleMsg> {
+    let field = match key_field(&e) {
+        Some(field) => field,
+        None => return Vec::new(),
+    };
+
+    match e.key().as_str() {
+        "Enter" => {
+            e.prevent_default();
+            // fields that update on change, like skills, only do so when they lose focus
+            let _ = field.blur();
+            vec![PeopleMsg::SavePerson(id)]
+        }
+        "Escape" => {
+            e.prevent_default();
+            vec![PeopleMsg::CancelPerson(id)]
         }
+        _ => Vec::new(),
     }
 }
 
+/// Asks before leaving the page while `unsaved` is set
+fn warn_before_unload(unsaved: Rc<Cell<bool>>) {
+    let window = match web_sys::window() {
+        Some(window) => window,
+        None => return,
+    };
+
+    let listener = Closure::wrap(Box::new(move |e: BeforeUnloadEvent| {
+        if unsaved.get() {
+            e.prevent_default();
+            e.set_return_value(t("There are unsaved changes to people"));
+        }
+    }) as Box<dyn FnMut(BeforeUnloadEvent)>);
+    if let Err(err) =
+        window.add_event_listener_with_callback("beforeunload", listener.as_ref().unchecked_ref())
+    {
+        error!("could not listen for beforeunload: {:?}", err);
+    }
+    // the listener lasts as long as the page
+    listener.forget();
+}
+
 impl PeopleModel {
-    fn from(
-        model: PeopleStore,
-        on_save: Option<Callback<usize>>,
-        link: ComponentLink<Self>,
-    ) -> Self {
-        Self {
-            inc: model.inc,
-            people: model.people.into_iter().map(|p| (p, false)).collect(),
-            on_save,
-            link,
+    /// Everyone as stored, leaving out unsaved changes
+    fn stored_people(&self) -> Vec<Person> {
+        self.people.iter().filter_map(|r| r.saved.clone()).collect()
+    }
+
+    /// Where the row is in the stored list of people
+    fn stored_index(&self, id: Id) -> usize {
+        self.people[..id]
+            .iter()
+            .filter(|r| r.saved.is_some())
+            .count()
+    }
+
+    /// Marks the row as saved, returns the edits that bring the stored people up to date with it
+    fn commit(&mut self, id: Id) -> Vec<PeopleOp> {
+        let idx = self.stored_index(id);
+        let row = &mut self.people[id];
+        row.is_editting = false;
+        let name = row.person.name().trim().to_string();
+        if name != row.person.name() {
+            row.person.set_name(name);
+        }
+
+        let person = &row.person;
+        let ops = match row.saved {
+            Some(ref saved) => {
+                let mut ops = Vec::new();
+                if saved.name() != person.name() {
+                    ops.push(PeopleOp::SetName(idx, person.name().to_string()));
+                }
+                if saved.ability() != person.ability() {
+                    ops.push(PeopleOp::SetAbility(idx, person.ability().clone()));
+                }
+                if saved.tags() != person.tags() {
+                    ops.push(PeopleOp::SetTags(idx, person.tags().clone()));
+                }
+                ops
+            }
+            None => vec![PeopleOp::Add(person.clone())],
+        };
+
+        let is_new = row.saved.is_none();
+        row.saved = Some(row.person.clone());
+
+        // new people are added to the end of the stored list, ahead of anyone still unsaved
+        if is_new {
+            let row = self.people.remove(id);
+            self.people.insert(idx, row);
+        }
+        ops
+    }
+
+    /// Stores the saved people, records the edits for the server and undo
+    fn store(&mut self, label: String, ops: Vec<PeopleOp>) {
+        debug!("saving PeopleModel: {}", label);
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        let mut people = PeopleStore {
+            inc: self.inc,
+            people: self.stored_people(),
+        };
+        history::tracked(&mut local_store, label, PEOPLE_KEY, |local_store| {
+            people.store(local_store)
+        });
+        self.inc = people.inc;
+
+        PeopleLogStore::record(&mut local_store, ops);
+        self.pending = PeopleLogStore::restore(&mut local_store).log.len();
+        sync::sync_people(self.link.callback(PeopleMsg::Synced));
+
+        if let Some(e) = self.on_save.as_ref() {
+            e.emit(self.inc)
+        }
+    }
+
+    /// Loads the stored people, e.g. after the server's were stored, keeping any unsaved edits
+    ///
+    /// Edits stay on top of the stored person with the same name as before. Edited people who are
+    ///  no longer stored are kept at the end, to be saved as new people.
+    fn reload(&mut self, local_store: &mut StorageService) {
+        if let Some(stored) = PeopleStore::restore(local_store) {
+            self.inc = stored.inc;
+            let mut dirty = self
+                .people
+                .drain(..)
+                .filter(PersonRow::is_dirty)
+                .collect::<Vec<_>>();
+            let mut people = stored
+                .people
+                .into_iter()
+                .map(|person| {
+                    let edited = dirty
+                        .iter()
+                        .position(|r| r.saved.as_ref().map(Person::name) == Some(person.name()));
+                    match edited {
+                        Some(idx) => PersonRow {
+                            saved: Some(person),
+                            ..dirty.remove(idx)
+                        },
+                        None => PersonRow::saved(person),
+                    }
+                })
+                .collect::<Vec<_>>();
+            people.extend(
+                dirty
+                    .into_iter()
+                    .map(|row| PersonRow { saved: None, ..row }),
+            );
+            self.people = people;
+
+            if let Some(e) = self.on_save.as_ref() {
+                e.emit(self.inc)
+            }
+        }
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.people.iter().any(PersonRow::is_dirty)
+    }
+
+    /// The problems with each row, as they are in the editor
+    fn errors(&self) -> Vec<PersonErrors> {
+        let people: Vec<Person> = self.people.iter().map(|r| r.person.clone()).collect();
+        validate_people(&people, &self.abilities)
+    }
+
+    fn view_sync(&self) -> Html {
+        match self.sync {
+            Some(SyncStatus::Conflict) => html! {
+                <div class="sync error">
+                    { t("Someone else changed the people on the server while these edits were waiting.") }{ " " }
+                    <button onclick=self.link.callback(|_| PeopleMsg::ResolveConflict(true))>{ t("Keep mine") }</button>
+                    <button onclick=self.link.callback(|_| PeopleMsg::ResolveConflict(false))>{ t("Use theirs") }</button>
+                </div>
+            },
+            _ if self.pending > 0 => html! {
+                <div class="sync">{ tf("{} saved edits waiting for the server", &[&self.pending]) }</div>
+            },
+            _ => html! {},
         }
     }
 }
@@ -92,78 +315,119 @@ impl Component for PeopleModel {
         debug!("creating PeopleModel");
 
         let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        let (abilities_version, abilities) = AbilitiesStore::restore_or_default(&mut local_store);
 
-        match PeopleStore::restore(&mut local_store) {
-            Some(this) => Self {
-                inc: this.inc,
-                people: this.people.into_iter().map(|p| (p, false)).collect(),
-                on_save: props.on_save,
-                link,
-            },
+        let people = match PeopleStore::restore(&mut local_store) {
+            Some(people) => people,
             None => {
-                let people = crate::default_people();
-                // TODO: make a borrowed type
                 let mut people = PeopleStore {
                     inc: 0,
-                    people: people,
+                    people: crate::default_people(),
                 };
 
                 people.store(&mut local_store);
-                Self {
-                    inc: people.inc,
-                    people: people.people.into_iter().map(|p| (p, false)).collect(),
-                    on_save: props.on_save,
-                    link,
-                }
+                people
             }
+        };
+
+        Self {
+            inc: people.inc,
+            people: people.people.into_iter().map(PersonRow::saved).collect(),
+            unsaved: Rc::new(Cell::new(false)),
+            pending: PeopleLogStore::restore(&mut local_store).log.len(),
+            sync: None,
+            history_version: props.history_version,
+            abilities_version,
+            abilities,
+            on_save: props.on_save,
+            link,
         }
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
-        match msg {
+        let render = match msg {
+            PeopleMsg::SavePeople if !self.errors().iter().all(PersonErrors::is_valid) => {
+                debug!("not saving people with errors");
+                true
+            }
             PeopleMsg::SavePeople => {
-                debug!("saving PeopleModel");
-                let mut local_store =
-                    StorageService::new(Area::Local).expect("failed to get storage");
-                let mut people: PeopleStore = self.clone().into();
-                people.store(&mut local_store);
-                *self = PeopleModel::from(people, self.on_save.take(), self.link.clone());
+                let mut ops = Vec::new();
+                while let Some(id) = self.people.iter().position(PersonRow::is_dirty) {
+                    ops.extend(self.commit(id));
+                }
+                for row in self.people.iter_mut() {
+                    row.is_editting = false;
+                }
 
-                self.on_save.as_ref().map(|e| e.emit(self.inc));
+                self.store(t("Save people").to_string(), ops);
                 true
             }
             PeopleMsg::AddPerson => {
                 debug!("adding a Person");
-                let person = Person::new("Jane Doe", Ability::Adult);
-                self.people.push((person, true));
+                let ability = self.abilities.first().cloned().unwrap_or_default();
+                self.people.push(PersonRow {
+                    person: Person::new("Jane Doe", ability),
+                    saved: None,
+                    is_editting: true,
+                });
                 true
             }
             PeopleMsg::EditPerson(id) => {
                 debug!("edit person: {}", id);
                 self.people
                     .get_mut(id)
-                    .map(|p| {
-                        if !p.1 {
-                            p.1 = true;
-                            true
-                        } else {
-                            false
-                        }
-                    })
+                    .map(|r| !std::mem::replace(&mut r.is_editting, true))
                     .unwrap_or(false)
             }
-            PeopleMsg::DeletePerson(idx) => {
-                let person = self.people.remove(idx);
-                debug!("deleted {:?}", person);
+            PeopleMsg::SavePerson(id)
+                if !self.errors().get(id).is_some_and(PersonErrors::is_valid) =>
+            {
+                debug!("not saving person with errors: {}", id);
+                true
+            }
+            PeopleMsg::SavePerson(id) => {
+                let label = tf("Save {}", &[&self.people[id].person.name()]);
+                let is_dirty = self.people[id].is_dirty();
+                let ops = self.commit(id);
+                if is_dirty {
+                    self.store(label, ops);
+                }
+                true
+            }
+            PeopleMsg::CancelPerson(id) if id < self.people.len() => {
+                let row = &mut self.people[id];
+                match row.saved {
+                    Some(ref saved) => {
+                        debug!("cancelling edits to {}", saved);
+                        row.person = saved.clone();
+                        row.is_editting = false;
+                    }
+                    None => {
+                        self.people.remove(id);
+                    }
+                }
+                true
+            }
+            PeopleMsg::CancelPerson(_) => false,
+            PeopleMsg::DeletePerson(id) if id < self.people.len() => {
+                let idx = self.stored_index(id);
+                let row = self.people.remove(id);
+                debug!("deleted {:?}", row.person);
+
+                // saved right away so that it can be undone
+                if let Some(saved) = row.saved {
+                    self.store(tf("Delete {}", &[&saved]), vec![PeopleOp::Delete(idx)]);
+                }
                 true
             }
+            PeopleMsg::DeletePerson(_) => false,
             PeopleMsg::PersonNameInput(id, name) => self
                 .people
                 .get_mut(id)
-                .map(|p| {
-                    debug!("saving name: {}", name);
-                    if p.0.name() != name {
-                        p.0.set_name(name);
+                .map(|r| {
+                    debug!("name: {}", name);
+                    if r.person.name() != name {
+                        r.person.set_name(name);
                         true
                     } else {
                         false
@@ -173,66 +437,155 @@ impl Component for PeopleModel {
             PeopleMsg::PersonAbilityInput(id, ability) => self
                 .people
                 .get_mut(id)
-                .map(|p| {
-                    debug!("saving name: {}", ability);
-                    if p.0.ability() != ability {
-                        p.0.set_ability(ability);
+                .map(|r| {
+                    debug!("ability: {}", ability);
+                    if *r.person.ability() != ability {
+                        r.person.set_ability(ability);
                         true
                     } else {
                         false
                     }
                 })
                 .unwrap_or(false),
-        }
+            PeopleMsg::PersonTagsInput(id, tags) => self
+                .people
+                .get_mut(id)
+                .map(|r| {
+                    debug!("tags: {}", tags_to_string(&tags));
+                    if *r.person.tags() != tags {
+                        r.person.set_tags(tags);
+                        true
+                    } else {
+                        false
+                    }
+                })
+                .unwrap_or(false),
+            PeopleMsg::Sync => {
+                sync::sync_people(self.link.callback(PeopleMsg::Synced));
+                false
+            }
+            PeopleMsg::Synced(status) => {
+                debug!("people sync: {:?}", status);
+                let mut local_store =
+                    StorageService::new(Area::Local).expect("failed to get storage");
+                if status == SyncStatus::Updated {
+                    self.reload(&mut local_store);
+                }
+
+                self.pending = PeopleLogStore::restore(&mut local_store).log.len();
+                self.sync = Some(status);
+                true
+            }
+            PeopleMsg::ResolveConflict(keep_mine) => {
+                sync::resolve_people(keep_mine, self.link.callback(PeopleMsg::Synced));
+                false
+            }
+        };
+
+        self.unsaved.set(self.is_dirty());
+        render
     }
 
-    fn view(&self) -> Html {
-        // let select = |is_selected: bool| {
-        //     html!{
-        //         <Select: is_selected={is_selected}, />
-        //     }
-        // };
+    fn mounted(&mut self) -> ShouldRender {
+        sync::sync_people(self.link.callback(PeopleMsg::Synced));
+        remote::on_online(self.link.callback(|_| PeopleMsg::Sync));
+        warn_before_unload(self.unsaved.clone());
+        false
+    }
 
-        let edit_delete = |id: Id, is_editting: IsEditting, link: &ComponentLink<Self>| {
-            let on_edit = link.callback(PeopleMsg::EditPerson);
-            let on_delete = link.callback(PeopleMsg::DeletePerson);
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let mut render = false;
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        if self.history_version != props.history_version {
+            debug!("reloading PeopleModel");
+            self.history_version = props.history_version;
+            self.reload(&mut local_store);
+            self.unsaved.set(self.is_dirty());
+            render = true;
+        }
 
-            html! {
-                <EditDelete: id={id}, is_editting={is_editting}, on_edit=on_edit, on_delete=on_delete, />
-            }
-        };
-        let person_row = |id: Id, person: &(Person, IsEditting), link: &ComponentLink<Self>| {
-            let name_on_input = link.callback(|(i, n)| PeopleMsg::PersonNameInput(i, n));
-            let ability_on_input = link.callback(|(i, a)| PeopleMsg::PersonAbilityInput(i, a));
+        if self.abilities_version != props.abilities_version {
+            debug!("updating PeopleModel abilities");
+            let (abilities_version, abilities) =
+                AbilitiesStore::restore_or_default(&mut local_store);
+            self.abilities_version = abilities_version;
+            self.abilities = abilities;
+            render = true;
+        }
+        render
+    }
+
+    fn view(&self) -> Html {
+        let errors = self.errors();
+        let is_valid = errors.iter().all(PersonErrors::is_valid);
+        let person_row = |id: Id, row: &PersonRow| {
+            let name_on_input = self
+                .link
+                .callback(|(i, n)| PeopleMsg::PersonNameInput(i, n));
+            let ability_on_input = self
+                .link
+                .callback(|(i, a)| PeopleMsg::PersonAbilityInput(i, a));
+            let tags_on_input = self
+                .link
+                .callback(|(i, t)| PeopleMsg::PersonTagsInput(i, t));
+            let (person, is_editting) = (&row.person, row.is_editting);
+            let dirty = if row.is_dirty() { "dirty" } else { "" };
+            let error = errors.get(id).copied().unwrap_or_default();
+
+            let keys = if is_editting {
+                self.link.batch_callback(move |e| row_keys(id, e))
+            } else {
+                self.link.batch_callback(|_: KeyboardEvent| Vec::new())
+            };
 
             html! {
-                <tr>
-                    <td><PersonName: id={id}, name={person.0.name().clone()}, is_editting={person.1}, on_input=name_on_input,/></td>
-                    <td><PersonAbility: id={id}, ability={person.0.ability()}, is_editting={person.1}, on_input=ability_on_input,/></td>
-                    <td class="edit_delete",>{ edit_delete(id, person.1, &self.link) }</td>
+                <tr class=dirty onkeydown=keys>
+                    <td><PersonName id=id name=person.name().to_string() is_editting=is_editting error=error.name on_input=name_on_input /></td>
+                    <td><PersonAbility id=id ability=person.ability().clone() abilities=self.abilities.clone() is_editting=is_editting error=error.ability on_input=ability_on_input /></td>
+                    <td><PersonTags id=id tags=person.tags().clone() is_editting=is_editting on_input=tags_on_input /></td>
+                    <td class="edit_delete">
+                        <EditDelete
+                            id=id
+                            label=person.name().to_string()
+                            is_editting=is_editting
+                            on_edit=self.link.callback(PeopleMsg::EditPerson)
+                            on_delete=self.link.callback(PeopleMsg::DeletePerson)
+                            on_save=self.link.callback(PeopleMsg::SavePerson)
+                            on_cancel=self.link.callback(PeopleMsg::CancelPerson) />
+                    </td>
                 </tr>
             }
         };
+        let unsaved = if !is_valid {
+            html! { <span class="unsaved error">{ t("Fix the marked people before saving") }</span> }
+        } else if self.is_dirty() {
+            html! { <span class="unsaved">{ t("Unsaved changes") }</span> }
+        } else {
+            html! {}
+        };
 
         html! {
             <>
-                <h2>{"All the beautiful people"}</h2>
+                <h2>{ t("All the beautiful people") }</h2>
+                { self.view_sync() }
                 <table>
                     <thead>
-                        <tr><th>{"Person"}</th><th>{"Ability"}</th><th>{" "}</th></tr>
+                        <tr><th>{ t("Person") }</th><th>{ t("Ability") }</th><th>{ t("Skills") }</th><th>{" "}</th></tr>
                     </thead>
                     <tbody>
-                        { for self.people.iter().enumerate().map(|(i, p)| person_row(i, p, &self.link)) }
+                        { for self.people.iter().enumerate().map(|(i, r)| person_row(i, r)) }
                     </tbody>
                     <tfoot>
-                        <tr><td>
-                            <button onclick=self.link.callback(|_| PeopleMsg::AddPerson), >
-                                <i class=("fa", "fa-plus-square"), aria-hidden="true",></i>
+                        <tr><td colspan="4">
+                            <button type="button" onclick=self.link.callback(|_| PeopleMsg::AddPerson)>
+                                <i class=("fa", "fa-plus-square") aria-hidden="true"></i>
+                                { " " }{ t("Add a person") }
                             </button>
-                            <button onclick=self.link.callback(|_| PeopleMsg::SavePeople), >
-                                <i class=("fa", "fa-floppy-o"), aria-hidden="true",></i>
+                            <button type="button" disabled=!is_valid onclick=self.link.callback(|_| PeopleMsg::SavePeople)>
+                                <i class=("fa", "fa-floppy-o") aria-hidden="true"></i>
+                                { " " }{ t("Save everyone") }
                             </button>
-                            //button onclick=|_| PeopleMsg::SavePeople, >{"Save all the People"}</button>
+                            { unsaved }
                         </td></tr>
                     </tfoot>
                 </table>
@@ -277,86 +630,23 @@ impl Component for PeopleModel {
 //     }
 // }
 
-/// EditDelete Component for a person row
-#[derive(Clone)]
-struct EditDelete {
-    id: Id,
-    is_editting: IsEditting,
-    on_edit: Option<Callback<Id>>,
-    on_delete: Option<Callback<Id>>,
-    link: ComponentLink<Self>,
-}
-
-#[derive(Clone, PartialEq, Default, Properties)]
-struct EditDeleteProps {
-    pub id: Id,
-    pub is_editting: IsEditting,
-    pub on_edit: Option<Callback<Id>>,
-    pub on_delete: Option<Callback<Id>>,
-}
-
-enum EditDeleteMsg {
-    Edit,
-    Delete,
-}
-
-impl Component for EditDelete {
-    type Message = EditDeleteMsg;
-    type Properties = EditDeleteProps;
-
-    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
-        Self {
-            id: props.id,
-            is_editting: props.is_editting,
-            on_edit: props.on_edit,
-            on_delete: props.on_delete,
-            link,
-        }
-    }
-
-    fn update(&mut self, msg: Self::Message) -> ShouldRender {
-        match msg {
-            EditDeleteMsg::Edit => {
-                debug!("editting: {}", self.id);
-                if !self.is_editting {
-                    self.on_edit.as_ref().map(|c| c.emit(self.id));
-                }
-            }
-            EditDeleteMsg::Delete => {
-                debug!("deleting: {}", self.id);
-                self.on_delete.as_ref().map(|c| c.emit(self.id));
-            }
-        }
-
-        false
-    }
-
-    fn change(&mut self, props: Self::Properties) -> ShouldRender {
-        if self.is_editting != props.is_editting {
-            self.is_editting = props.is_editting;
-            return true;
-        }
-        false
-    }
-
-    fn view(&self) -> Html {
-        let disabled = if self.is_editting { "disabled" } else { "" };
-
-        html! {
-            <div class="edit_delete", >
-                <i class=("fa", "fa-pencil-square-o", "fa-fw", disabled), aria-hidden="true", onclick=self.link.callback(|_| EditDeleteMsg::Edit), />
-                <i class=("fa", "fa-trash", "fa-fw"), aria-hidden="true", onclick=self.link.callback(|_| EditDeleteMsg::Delete), />
-            </div>
-        }
+/// Shows what's wrong with a field next to it
+fn view_error(error: Option<Invalid>) -> Html {
+    match error {
+        Some(error) => html! { <div class="field_error error">{ t(error.message()) }</div> },
+        None => html! {},
     }
 }
 
-#[derive(Clone)]
 struct PersonName {
     id: Id,
     name: String,
     is_editting: IsEditting,
+    error: Option<Invalid>,
     on_input: Option<Callback<(Id, String)>>,
+    input: NodeRef,
+    /// Focuses the name once it's drawn for editing
+    focus_task: Option<TimeoutTask>,
     link: ComponentLink<Self>,
 }
 
@@ -365,11 +655,23 @@ struct PersonNameProps {
     pub id: Id,
     pub name: String,
     pub is_editting: IsEditting,
+    #[prop_or_default]
+    pub error: Option<Invalid>,
     pub on_input: Option<Callback<(Id, String)>>,
 }
 
 enum PersonNameMsg {
     Input(String),
+    Focus,
+}
+
+impl PersonName {
+    fn focus_later(&mut self) {
+        self.focus_task = Some(TimeoutService::new().spawn(
+            Duration::from_millis(0),
+            self.link.callback(|_| PersonNameMsg::Focus),
+        ));
+    }
 }
 
 impl Component for PersonName {
@@ -381,17 +683,34 @@ impl Component for PersonName {
             id: props.id,
             name: props.name.clone(),
             is_editting: props.is_editting,
+            error: props.error,
             on_input: props.on_input,
+            input: NodeRef::default(),
+            focus_task: None,
             link,
         }
     }
 
+    fn mounted(&mut self) -> ShouldRender {
+        // a person that was just added
+        if self.is_editting {
+            self.focus_later();
+        }
+        false
+    }
+
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
+            PersonNameMsg::Focus => {
+                self.focus_task = None;
+                crate::web::focus(&self.input, false);
+            }
             PersonNameMsg::Input(n) => {
                 debug!("input: {}, {}", self.id, self.name);
                 if self.is_editting {
-                    self.on_input.as_ref().map(|c| c.emit((self.id, n)));
+                    if let Some(c) = self.on_input.as_ref() {
+                        c.emit((self.id, n))
+                    }
                 }
             }
         }
@@ -403,6 +722,9 @@ impl Component for PersonName {
         let mut render = false;
         if self.is_editting != props.is_editting {
             self.is_editting = props.is_editting;
+            if self.is_editting {
+                self.focus_later();
+            }
             render |= true;
         }
 
@@ -410,18 +732,30 @@ impl Component for PersonName {
             self.name = props.name;
             render |= true;
         }
+
+        if self.error != props.error {
+            self.error = props.error;
+            render |= true;
+        }
         render
     }
 
     fn view(&self) -> Html {
-        if self.is_editting {
+        let name = if self.is_editting {
             html! {
-                <input type="text", value={&self.name}, oninput=self.link.callback(|e: InputData| PersonNameMsg::Input(e.value)), />
+                <input type="text" ref=self.input.clone() aria-label=t("Person") aria-invalid=self.error.is_some().to_string() value={&self.name} oninput=self.link.callback(|e: InputData| PersonNameMsg::Input(e.value)) />
             }
         } else {
             html! {
                 <>{ &self.name }</>
             }
+        };
+
+        html! {
+            <>
+                { name }
+                { view_error(self.error) }
+            </>
         }
     }
 }
@@ -430,7 +764,9 @@ impl Component for PersonName {
 struct PersonAbility {
     id: Id,
     ability: Ability,
+    abilities: Abilities,
     is_editting: IsEditting,
+    error: Option<Invalid>,
     on_input: Option<Callback<(Id, Ability)>>,
     link: ComponentLink<Self>,
 }
@@ -438,7 +774,10 @@ struct PersonAbility {
 struct PersonAbilityProps {
     pub id: Id,
     pub ability: Ability,
+    pub abilities: Abilities,
     pub is_editting: IsEditting,
+    #[prop_or_default]
+    pub error: Option<Invalid>,
     pub on_input: Option<Callback<(Id, Ability)>>,
 }
 
@@ -453,8 +792,10 @@ impl Component for PersonAbility {
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
         Self {
             id: props.id,
-            ability: props.ability.clone(),
+            ability: props.ability,
+            abilities: props.abilities,
             is_editting: props.is_editting,
+            error: props.error,
             on_input: props.on_input,
             link,
         }
@@ -463,14 +804,13 @@ impl Component for PersonAbility {
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             PersonAbilityMsg::Input(se) => {
-                debug!("input: {}, {:?}", self.id, se.selected_index());
-
-                let enum_i32: i32 = se.selected_index();
-                let ability = Ability::from_i32(enum_i32);
+                let ability = Ability::new(se.value());
 
                 debug!("input: {}, {}", self.id, ability);
                 if self.is_editting {
-                    self.on_input.as_ref().map(|c| c.emit((self.id, ability)));
+                    if let Some(c) = self.on_input.as_ref() {
+                        c.emit((self.id, ability))
+                    }
                 }
             }
         }
@@ -489,35 +829,138 @@ impl Component for PersonAbility {
             self.ability = props.ability;
             render |= true;
         }
+
+        if self.abilities != props.abilities {
+            self.abilities = props.abilities;
+            render |= true;
+        }
+
+        if self.error != props.error {
+            self.error = props.error;
+            render |= true;
+        }
         render
     }
 
     fn view(&self) -> Html {
         if self.is_editting {
-            let select_ability = |ability: Ability| {
-                let value = i32::from(ability).to_string();
-                if self.ability == ability {
+            let select_ability = |ability: &Ability| {
+                let value = ability.to_str().to_string();
+                if self.ability == *ability {
                     html! {
-                        <option value={value}, selected=true, >{ ability.to_str() }</option>
+                        <option value={value}, selected=true, >{ i18n::ability_name(ability) }</option>
                     }
                 } else {
                     html! {
-                        <option value={value}, >{ ability.to_str() }</option>
+                        <option value={value}, >{ i18n::ability_name(ability) }</option>
                     }
                 }
             };
+            // keep showing a group that has since been removed from the list
+            let removed = if self.abilities.contains(&self.ability) {
+                None
+            } else {
+                Some(&self.ability)
+            };
+
+            html! {
+                <>
+                    <select aria-label=t("Ability") aria-invalid=self.error.is_some().to_string() onchange=self.link.callback(|e| match e {
+                        ChangeData::Select(se) => PersonAbilityMsg::Input(se),
+                        _ => unreachable!(),
+                    }),>
+                       { for self.abilities.iter().chain(removed).map(select_ability) }
+                    </select>
+                    { view_error(self.error) }
+                </>
+            }
+        } else {
+            html! {
+                <>
+                    { i18n::ability_name(&self.ability) }
+                    { view_error(self.error) }
+                </>
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct PersonTags {
+    id: Id,
+    tags: Tags,
+    is_editting: IsEditting,
+    on_input: Option<Callback<(Id, Tags)>>,
+    link: ComponentLink<Self>,
+}
+
+#[derive(Clone, PartialEq, Default, Properties)]
+struct PersonTagsProps {
+    pub id: Id,
+    pub tags: Tags,
+    pub is_editting: IsEditting,
+    pub on_input: Option<Callback<(Id, Tags)>>,
+}
+
+enum PersonTagsMsg {
+    Input(String),
+}
 
+impl Component for PersonTags {
+    type Message = PersonTagsMsg;
+    type Properties = PersonTagsProps;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            id: props.id,
+            tags: props.tags,
+            is_editting: props.is_editting,
+            on_input: props.on_input,
+            link,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            PersonTagsMsg::Input(t) => {
+                debug!("input: {}, {}", self.id, t);
+                if self.is_editting {
+                    if let Some(c) = self.on_input.as_ref() {
+                        c.emit((self.id, parse_tags(&t)))
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let mut render = false;
+        if self.is_editting != props.is_editting {
+            self.is_editting = props.is_editting;
+            render |= true;
+        }
+
+        if self.tags != props.tags {
+            self.tags = props.tags;
+            render |= true;
+        }
+        render
+    }
+
+    fn view(&self) -> Html {
+        // tags are only parsed on change so that typing a comma doesn't get normalized away
+        if self.is_editting {
             html! {
-                <select onchange=self.link.callback(|e| match e {
-                    ChangeData::Select(se) => PersonAbilityMsg::Input(se),
+                <input type="text", aria-label=t("Skills"), placeholder="driver, stove", value={tags_to_string(&self.tags)}, onchange=self.link.callback(|e| match e {
+                    ChangeData::Value(v) => PersonTagsMsg::Input(v),
                     _ => unreachable!(),
-                }),>
-                   { for Ability::enumerate().iter().map(|a| select_ability(*a)) }
-                </select>
+                }), />
             }
         } else {
             html! {
-                <>{ self.ability.to_str() }</>
+                <>{ tags_to_string(&self.tags) }</>
             }
         }
     }
@@ -0,0 +1,78 @@
+use qrcode::render::svg;
+use qrcode::{EcLevel, QrCode};
+use yew::prelude::*;
+use yew::virtual_dom::VNode;
+
+use crate::web::i18n::t;
+
+/// Smallest size the code is drawn at, in pixels, big enough to scan from a printout
+const MIN_SIZE: u32 = 160;
+
+/// The link as an SVG QR code, None if it's too long to fit in one
+pub fn qr_svg(link: &str) -> Option<String> {
+    let code = QrCode::with_error_correction_level(link, EcLevel::L).ok()?;
+    Some(
+        code.render::<svg::Color>()
+            .min_dimensions(MIN_SIZE, MIN_SIZE)
+            .build(),
+    )
+}
+
+/// A QR code for a link, so it can be opened by scanning a printed chart
+pub struct QrModel {
+    link: String,
+    svg: Option<String>,
+}
+
+#[derive(Clone, Default, PartialEq, Properties)]
+pub struct QrProps {
+    pub link: String,
+}
+
+impl Component for QrModel {
+    type Message = ();
+    type Properties = QrProps;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self {
+            svg: qr_svg(&props.link),
+            link: props.link,
+        }
+    }
+
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender {
+        false
+    }
+
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if self.link != props.link {
+            self.svg = qr_svg(&props.link);
+            self.link = props.link;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn view(&self) -> Html {
+        let svg = match self.svg {
+            Some(ref svg) => svg,
+            None => {
+                return html! { <p>{ t("This chart is too big for a QR code, share the link instead") }</p> }
+            }
+        };
+
+        // the SVG is markup, which html! would escape, so it goes through a detached element
+        let element = web_sys::window()
+            .and_then(|w| w.document())
+            .and_then(|d| d.create_element("div").ok());
+        match element {
+            Some(element) => {
+                element.set_class_name("qr_code");
+                element.set_inner_html(svg);
+                VNode::VRef(element.into())
+            }
+            None => html! {},
+        }
+    }
+}
@@ -0,0 +1,555 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+
+use log::{debug, error, warn};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{Request, RequestInit, Response};
+use yew::callback::Callback;
+use yew::format::{Json, Text};
+use yew::prelude::*;
+use yew::services::{storage::Area, StorageService};
+
+use crate::web::abilities::ABILITIES_KEY;
+use crate::web::chart::WEEK_KEY;
+use crate::web::i18n::{t, tf};
+use crate::web::jobs::JOBS_KEY;
+use crate::web::settings::SETTINGS_KEY;
+use crate::web::workspace;
+
+pub(crate) const SERVER_KEY: &str = "server_v1";
+pub(crate) const SYNCED_KEY: &str = "synced_v1";
+
+/// The server's name for each locally stored document, people are sent as edits by `sync`
+const COLLECTIONS: [(&str, &str); 4] = [
+    ("jobs", JOBS_KEY),
+    ("abilities", ABILITIES_KEY),
+    ("settings", SETTINGS_KEY),
+    ("week", WEEK_KEY),
+];
+
+/// The kp-chart-server to share the roster through, local storage only if it's not set
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ServerStore {
+    pub url: String,
+}
+
+impl ServerStore {
+    pub fn restore(local_store: &mut StorageService) -> Self {
+        match local_store.restore(&workspace::key(SERVER_KEY)) {
+            Json(Ok(server)) => server,
+            Json(Err(_)) => Self::default(),
+        }
+    }
+
+    pub fn store(&self, local_store: &mut StorageService) {
+        debug!("saving server: {}", self.url);
+        local_store.store(&workspace::key(SERVER_KEY), Json(self));
+    }
+
+    pub(crate) fn api_url(&self, collection: &str) -> Option<String> {
+        let url = self.url.trim().trim_end_matches('/');
+        if url.is_empty() {
+            None
+        } else {
+            Some(format!("{}/api/{}", url, collection))
+        }
+    }
+}
+
+/// What this browser knows of the server's copy of each document
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct SyncedStore {
+    /// The server's version of each document when this browser last had the same copy
+    #[serde(default)]
+    pub versions: BTreeMap<String, usize>,
+    /// Documents saved here since, that the server doesn't have yet
+    #[serde(default)]
+    pub changed: BTreeSet<String>,
+    /// Documents changed both here and on the server, until someone picks which copy to keep
+    #[serde(default)]
+    pub conflicts: BTreeSet<String>,
+}
+
+impl SyncedStore {
+    pub fn restore(local_store: &mut StorageService) -> Self {
+        match local_store.restore(&workspace::key(SYNCED_KEY)) {
+            Json(Ok(synced)) => synced,
+            Json(Err(_)) => Self::default(),
+        }
+    }
+
+    pub fn store(&self, local_store: &mut StorageService) {
+        local_store.store(&workspace::key(SYNCED_KEY), Json(self));
+    }
+
+    /// Changes what's stored, reading it first so nothing stored while waiting on the server is lost
+    fn update<F: FnOnce(&mut Self)>(local_store: &mut StorageService, f: F) {
+        let mut synced = Self::restore(local_store);
+        f(&mut synced);
+        synced.store(local_store);
+    }
+}
+
+/// Just the version of any stored document
+#[derive(Deserialize)]
+pub(crate) struct Versioned {
+    pub inc: usize,
+}
+
+/// The response status and body, Err if the server couldn't be reached
+pub(crate) async fn request(
+    method: &str,
+    url: &str,
+    body: Option<String>,
+) -> Result<(u16, String), JsValue> {
+    let init = RequestInit::new();
+    init.set_method(method);
+    if let Some(ref body) = body {
+        init.set_body(&JsValue::from_str(body));
+    }
+
+    let request = Request::new_with_str_and_init(url, &init)?;
+    request.headers().set("Content-Type", "application/json")?;
+
+    let window = web_sys::window().ok_or("no window")?;
+    let response: Response = JsFuture::from(window.fetch_with_request(&request))
+        .await?
+        .dyn_into()?;
+    let text = JsFuture::from(response.text()?).await?;
+    debug!("{} {}: {}", method, url, response.status());
+    Ok((response.status(), text.as_string().unwrap_or_default()))
+}
+
+/// The response body, or None if the server doesn't have the document
+pub(crate) async fn fetch(
+    method: &str,
+    url: &str,
+    body: Option<String>,
+) -> Result<Option<String>, JsValue> {
+    match request(method, url, body).await? {
+        (404, _) => Ok(None),
+        (status, text) if (200..300).contains(&status) => Ok(Some(text)),
+        (status, _) => Err(format!("{} {} failed: {}", method, url, status).into()),
+    }
+}
+
+/// Calls back whenever the browser comes back online
+pub(crate) fn on_online(callback: Callback<()>) {
+    let window = match web_sys::window() {
+        Some(window) => window,
+        None => return,
+    };
+
+    let listener = Closure::wrap(Box::new(move || callback.emit(())) as Box<dyn FnMut()>);
+    if let Err(err) =
+        window.add_event_listener_with_callback("online", listener.as_ref().unchecked_ref())
+    {
+        error!("could not listen for online: {:?}", err);
+    }
+    // the listener lasts as long as the page
+    listener.forget();
+}
+
+/// Sends a document changed from the server's version `base`, returns the version the server gave
+///  it, or None if someone else saved it since `base`
+pub(crate) async fn put(url: &str, base: usize, body: String) -> Result<Option<usize>, JsValue> {
+    match request("PUT", &format!("{}?base={}", url, base), Some(body)).await? {
+        (200, text) => {
+            let Versioned { inc } =
+                serde_json::from_str(&text).map_err(|e| JsValue::from(e.to_string()))?;
+            Ok(Some(inc))
+        }
+        (409, _) => Ok(None),
+        (status, text) => Err(format!("PUT {} failed: {} {}", url, status, text).into()),
+    }
+}
+
+thread_local! {
+    /// Documents to sync once the sync underway is done, None while there isn't one
+    static WAITING: RefCell<Option<BTreeSet<&'static str>>> = const { RefCell::new(None) };
+    static ON_SYNCED: RefCell<Option<Callback<()>>> = const { RefCell::new(None) };
+}
+
+fn local_key(collection: &str) -> &'static str {
+    COLLECTIONS
+        .iter()
+        .find(|(c, _)| *c == collection)
+        .map(|(_, key)| *key)
+        .expect("a synced collection")
+}
+
+fn restore_text(local_store: &mut StorageService, key: &str) -> Option<String> {
+    let text: Text = local_store.restore(&workspace::key(key));
+    text.ok()
+}
+
+/// Takes the server's copy of a document, returns true as it's now changed locally
+fn take_remote(collection: &'static str, text: String, inc: usize) -> bool {
+    debug!("{} is newer on the server: {}", collection, inc);
+    let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+    let remote: Text = Ok(text);
+    local_store.store(&workspace::key(local_key(collection)), remote);
+    SyncedStore::update(&mut local_store, |synced| {
+        synced.versions.insert(collection.to_string(), inc);
+        synced.changed.remove(collection);
+        synced.conflicts.remove(collection);
+    });
+    true
+}
+
+/// Sends the local copy of a document changed from the server's version `base`
+async fn send(collection: &'static str, url: &str, base: usize) -> Result<(), JsValue> {
+    let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+    let key = local_key(collection);
+    let body = match restore_text(&mut local_store, key) {
+        Some(body) => body,
+        None => return Ok(()),
+    };
+
+    debug!("{} is newer locally than version {}", collection, base);
+    let inc = put(url, base, body.clone()).await?;
+    let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+    // it may have been saved again while waiting, then it's still changed
+    let unchanged = restore_text(&mut local_store, key).as_ref() == Some(&body);
+    SyncedStore::update(&mut local_store, |synced| match inc {
+        Some(inc) => {
+            synced.versions.insert(collection.to_string(), inc);
+            if unchanged {
+                synced.changed.remove(collection);
+            }
+        }
+        None => {
+            warn!(
+                "{} changed on the server since version {}",
+                collection, base
+            );
+            synced.conflicts.insert(collection.to_string());
+        }
+    });
+    Ok(())
+}
+
+/// Brings one document up to date with the server, returns true if the server's copy was taken
+///
+/// A document changed on only one side is copied to the other, one changed on both is a conflict
+///  until someone picks which to keep.
+async fn sync_one(collection: &'static str) -> Result<bool, JsValue> {
+    let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+    let url = match ServerStore::restore(&mut local_store).api_url(collection) {
+        Some(url) => url,
+        None => return Ok(false),
+    };
+    let synced = SyncedStore::restore(&mut local_store);
+    if synced.conflicts.contains(collection) {
+        return Ok(false);
+    }
+    let base = synced.versions.get(collection).copied();
+    let changed = synced.changed.contains(collection);
+
+    let remote = fetch("GET", &url, None).await?;
+    let remote_inc = remote
+        .as_ref()
+        .and_then(|t| serde_json::from_str::<Versioned>(t).ok())
+        .map(|v| v.inc);
+    let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+    let has_local = restore_text(&mut local_store, local_key(collection)).is_some();
+
+    match (remote, remote_inc) {
+        (None, _) => {
+            // the first browser to use the server shares its copy
+            send(collection, &url, 0).await?;
+            Ok(false)
+        }
+        (Some(_), Some(remote_inc)) if base == Some(remote_inc) => {
+            if changed {
+                send(collection, &url, remote_inc).await?;
+            }
+            Ok(false)
+        }
+        (Some(_), Some(_)) if changed && has_local => {
+            warn!("{} changed here and on the server", collection);
+            SyncedStore::update(&mut local_store, |synced| {
+                synced.conflicts.insert(collection.to_string());
+            });
+            Ok(false)
+        }
+        (Some(text), Some(remote_inc)) => Ok(take_remote(collection, text, remote_inc)),
+        (Some(_), None) => Err(format!("{} on the server has no version", collection).into()),
+    }
+}
+
+/// Syncs the documents, after any sync that's already underway
+fn sync(collections: Vec<&'static str>) {
+    let start = WAITING.with(|waiting| {
+        let mut waiting = waiting.borrow_mut();
+        match waiting.as_mut() {
+            Some(waiting) => {
+                waiting.extend(collections.iter().copied());
+                false
+            }
+            None => {
+                *waiting = Some(BTreeSet::new());
+                true
+            }
+        }
+    });
+    if !start {
+        return;
+    }
+
+    spawn_local(async move {
+        let mut collections = collections;
+        let mut updated = false;
+        loop {
+            for collection in collections {
+                match sync_one(collection).await {
+                    Ok(taken) => updated |= taken,
+                    Err(err) => warn!(
+                        "{} kept locally, the server is unavailable: {:?}",
+                        collection, err
+                    ),
+                }
+            }
+
+            let next = WAITING.with(|waiting| {
+                let mut waiting = waiting.borrow_mut();
+                let next = waiting.take().unwrap_or_default();
+                if !next.is_empty() {
+                    *waiting = Some(BTreeSet::new());
+                }
+                next
+            });
+            if next.is_empty() {
+                break;
+            }
+            collections = next.into_iter().collect();
+        }
+
+        synced(updated);
+    });
+}
+
+/// Lets the server picker know a sync is done, and starts over with anything new from the server
+fn synced(updated: bool) {
+    // every editor reads from storage when created, so start over with the server's copy
+    if updated {
+        crate::web::reload();
+        return;
+    }
+    ON_SYNCED.with(|on_synced| {
+        if let Some(callback) = on_synced.borrow().as_ref() {
+            callback.emit(())
+        }
+    });
+}
+
+/// Sends a document that was just stored locally to the server, if there is one
+///
+/// The document is only sent if it's still the server's copy it was changed from, otherwise the
+///  two are a conflict. Until it's sent it's kept as changed, and sent again by the next `pull`.
+pub fn push(collection: &'static str) {
+    let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+    if ServerStore::restore(&mut local_store)
+        .api_url(collection)
+        .is_none()
+    {
+        return;
+    }
+
+    SyncedStore::update(&mut local_store, |synced| {
+        synced.changed.insert(collection.to_string());
+    });
+    sync(vec![collection]);
+}
+
+/// Sends a document by its local storage key, for documents that were put back rather than saved
+pub(crate) fn push_document(key: &str) {
+    if let Some((collection, _)) = COLLECTIONS.iter().find(|(_, k)| *k == key) {
+        push(collection);
+    }
+}
+
+/// Brings local storage and the server up to date with each other
+///
+/// The page is reloaded if anything newer came from the server.
+pub fn pull() {
+    let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+    if ServerStore::restore(&mut local_store).api_url("").is_none() {
+        return;
+    }
+
+    sync(
+        COLLECTIONS
+            .iter()
+            .map(|(collection, _)| *collection)
+            .collect(),
+    );
+}
+
+/// Settles a conflict by sending this copy over the server's, or taking the server's
+async fn resolve(collection: &'static str, keep_mine: bool) -> Result<bool, JsValue> {
+    let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+    let url = match ServerStore::restore(&mut local_store).api_url(collection) {
+        Some(url) => url,
+        None => return Ok(false),
+    };
+
+    let remote = fetch("GET", &url, None).await?;
+    let remote_inc = remote
+        .as_ref()
+        .and_then(|t| serde_json::from_str::<Versioned>(t).ok())
+        .map(|v| v.inc);
+    let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+    SyncedStore::update(&mut local_store, |synced| {
+        synced.conflicts.remove(collection);
+    });
+
+    match (keep_mine, remote, remote_inc) {
+        (false, Some(text), Some(remote_inc)) => Ok(take_remote(collection, text, remote_inc)),
+        (_, _, remote_inc) => {
+            send(collection, &url, remote_inc.unwrap_or(0)).await?;
+            Ok(false)
+        }
+    }
+}
+
+fn spawn_resolve(collection: &'static str, keep_mine: bool) {
+    spawn_local(async move {
+        let updated = resolve(collection, keep_mine).await.unwrap_or_else(|err| {
+            warn!(
+                "{} kept locally, the server is unavailable: {:?}",
+                collection, err
+            );
+            false
+        });
+        synced(updated);
+    });
+}
+
+/// What the conflict is in, for the server picker
+fn collection_label(collection: &str) -> &'static str {
+    t(match collection {
+        "jobs" => "The jobs",
+        "abilities" => "The age groups",
+        "settings" => "The chart's dates",
+        _ => "The chart",
+    })
+}
+
+pub enum ServerMsg {
+    UrlInput(String),
+    Save,
+    Pull,
+    Synced,
+    Resolve(&'static str, bool),
+}
+
+/// Picks the server to share the roster through
+pub struct ServerModel {
+    server: ServerStore,
+    url: String,
+    conflicts: BTreeSet<String>,
+    link: ComponentLink<Self>,
+}
+
+impl Component for ServerModel {
+    type Message = ServerMsg;
+    type Properties = ();
+
+    fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        let server = ServerStore::restore(&mut local_store);
+
+        Self {
+            url: server.url.clone(),
+            server,
+            conflicts: SyncedStore::restore(&mut local_store).conflicts,
+            link,
+        }
+    }
+
+    fn mounted(&mut self) -> ShouldRender {
+        let on_synced = self.link.callback(|_| ServerMsg::Synced);
+        ON_SYNCED.with(|callback| *callback.borrow_mut() = Some(on_synced));
+        pull();
+        on_online(self.link.callback(|_| ServerMsg::Pull));
+        false
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            ServerMsg::UrlInput(url) => {
+                self.url = url;
+                false
+            }
+            ServerMsg::Save => {
+                let mut local_store =
+                    StorageService::new(Area::Local).expect("failed to get storage");
+                let url = self.url.trim().to_string();
+                if url != self.server.url {
+                    // versions from another server don't mean anything to this one
+                    SyncedStore::default().store(&mut local_store);
+                    self.conflicts.clear();
+                }
+                self.server.url = url;
+                self.server.store(&mut local_store);
+                pull();
+                true
+            }
+            ServerMsg::Pull => {
+                pull();
+                false
+            }
+            ServerMsg::Synced => {
+                let mut local_store =
+                    StorageService::new(Area::Local).expect("failed to get storage");
+                let conflicts = SyncedStore::restore(&mut local_store).conflicts;
+                let changed = self.conflicts != conflicts;
+                self.conflicts = conflicts;
+                changed
+            }
+            ServerMsg::Resolve(collection, keep_mine) => {
+                debug!("resolving {}, keeping mine: {}", collection, keep_mine);
+                self.conflicts.remove(collection);
+                spawn_resolve(collection, keep_mine);
+                true
+            }
+        }
+    }
+
+    fn view(&self) -> Html {
+        let status = if self.server.url.is_empty() {
+            t("Saved in this browser only").to_string()
+        } else {
+            tf("Shared through {}", &[&self.server.url])
+        };
+        let conflict = |(collection, _): &(&'static str, &str)| {
+            if !self.conflicts.contains(*collection) {
+                return html! {};
+            }
+
+            let collection = *collection;
+            html! {
+                <div class="sync error">
+                    { tf("{} changed here and on the server.", &[&collection_label(collection)]) }{ " " }
+                    <button onclick=self.link.callback(move |_| ServerMsg::Resolve(collection, true))>{ t("Keep mine") }</button>
+                    <button onclick=self.link.callback(move |_| ServerMsg::Resolve(collection, false))>{ t("Use theirs") }</button>
+                </div>
+            }
+        };
+
+        html! {
+            <div class="server">
+                <input type="url" placeholder="https://camp.example.com" value={&self.url} oninput=self.link.callback(|e: InputData| ServerMsg::UrlInput(e.value)) />
+                <button onclick=self.link.callback(|_| ServerMsg::Save)>
+                    <i class=("fa", "fa-cloud") aria-hidden="true"></i>
+                </button>
+                <span>{ status }</span>
+                { for COLLECTIONS.iter().map(conflict) }
+            </div>
+        }
+    }
+}
@@ -1,15 +1,110 @@
-use log::debug;
+use log::{debug, error};
+use wasm_bindgen::JsCast;
+use web_sys::{Element, KeyboardEvent};
 use yew::prelude::*;
+use yew::services::keyboard::{KeyListenerHandle, KeyboardService};
+use yew::services::{storage::Area, StorageService};
 
+use crate::data::SharedChart;
+use crate::web::chart::WeekStore;
+use crate::web::export::store_roster;
+use crate::web::history::{self, HistoryStore};
+use crate::web::i18n::{self, t, tf, Language};
+use crate::web::share;
+use crate::web::workspace::WorkspaceModel;
 use crate::web::*;
 
 pub struct RootModel {
     people_version: usize,
+    jobs_version: usize,
+    abilities_version: usize,
+    archive_version: usize,
+    /// Changes whenever the roster or the chart might have, for the export
+    roster_version: usize,
+    /// Bumped on every undo and redo, so everything reloads from storage
+    history_version: usize,
+    /// The chart from a shared link, if the page was opened from one
+    shared: Option<SharedChart>,
+    share_error: Option<String>,
+    _key_listener: Option<KeyListenerHandle>,
     link: ComponentLink<Self>,
 }
 
 pub enum RootMsg {
     PeopleUpdated(usize),
+    JobsUpdated(usize),
+    AbilitiesUpdated(usize),
+    ArchiveUpdated(usize),
+    ImportShared,
+    LeaveShared,
+    ChartChanged,
+    Undo,
+    Redo,
+    KeyDown(KeyboardEvent),
+    Language(Language),
+}
+
+/// Ctrl or ⌘ Z undoes, with shift or Y instead of Z it redoes, except in text fields which have their own undo
+fn shortcut(event: &KeyboardEvent) -> Option<RootMsg> {
+    if !(event.ctrl_key() || event.meta_key()) {
+        return None;
+    }
+
+    let in_field = event
+        .target()
+        .and_then(|t| t.dyn_into::<Element>().ok())
+        .is_some_and(|e| matches!(e.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT"));
+    if in_field {
+        return None;
+    }
+
+    match event.key().to_lowercase().as_str() {
+        "z" if event.shift_key() => Some(RootMsg::Redo),
+        "z" => Some(RootMsg::Undo),
+        "y" => Some(RootMsg::Redo),
+        _ => None,
+    }
+}
+
+impl RootModel {
+    fn view_language(&self) -> Html {
+        let option = |language: &Language| {
+            html! {
+                <option selected=*language == i18n::language()>{ language.name() }</option>
+            }
+        };
+
+        html! {
+            <label class="language">{ t("Language") }{" "}
+                <select onchange=self.link.callback(|e| match e {
+                    ChangeData::Select(se) => RootMsg::Language(Language::ALL.get(se.selected_index() as usize).copied().unwrap_or_default()),
+                    _ => unreachable!(),
+                })>
+                    { for Language::ALL.iter().map(option) }
+                </select>
+            </label>
+        }
+    }
+
+    fn view_history(&self) -> Html {
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        let history = HistoryStore::restore(&mut local_store).history;
+        let undo = history.last_done().map(|c| c.label.clone());
+        let redo = history.last_undone().map(|c| c.label.clone());
+
+        html! {
+            <div class="history">
+                <button disabled=undo.is_none() title=undo.clone().unwrap_or_default() onclick=self.link.callback(|_| RootMsg::Undo)>
+                    <i class=("fa", "fa-undo") aria-hidden="true"></i>
+                    { " " }{ undo.map_or_else(|| t("Undo").to_string(), |l| tf("Undo {}", &[&l])) }
+                </button>
+                <button disabled=redo.is_none() title=redo.clone().unwrap_or_default() onclick=self.link.callback(|_| RootMsg::Redo)>
+                    <i class=("fa", "fa-repeat") aria-hidden="true"></i>
+                    { " " }{ redo.map_or_else(|| t("Redo").to_string(), |l| tf("Redo {}", &[&l])) }
+                </button>
+            </div>
+        }
+    }
 }
 
 impl Component for RootModel {
@@ -19,8 +114,29 @@ impl Component for RootModel {
     type Properties = ();
 
     fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let (shared, share_error) = match share::shared_from_location() {
+            Some(Ok(shared)) => (Some(shared), None),
+            Some(Err(err)) => {
+                error!("could not open shared chart: {}", err);
+                (None, Some(err.to_string()))
+            }
+            None => (None, None),
+        };
+
+        let key_listener = web_sys::window().map(|window| {
+            KeyboardService::register_key_down(&window, link.callback(RootMsg::KeyDown))
+        });
+
         RootModel {
             people_version: 0,
+            jobs_version: 0,
+            abilities_version: 0,
+            archive_version: 0,
+            roster_version: 0,
+            history_version: 0,
+            shared,
+            share_error,
+            _key_listener: key_listener,
             link,
         }
     }
@@ -31,20 +147,146 @@ impl Component for RootModel {
                 debug!("root people version: {}", version);
                 if self.people_version != version {
                     self.people_version = version;
+                    self.roster_version += 1;
+                    true
+                } else {
+                    false
+                }
+            }
+            RootMsg::JobsUpdated(version) => {
+                debug!("root jobs version: {}", version);
+                if self.jobs_version != version {
+                    self.jobs_version = version;
+                    self.roster_version += 1;
                     true
                 } else {
                     false
                 }
             }
+            RootMsg::AbilitiesUpdated(version) => {
+                debug!("root abilities version: {}", version);
+                if self.abilities_version != version {
+                    self.abilities_version = version;
+                    self.roster_version += 1;
+                    true
+                } else {
+                    false
+                }
+            }
+            RootMsg::ArchiveUpdated(version) => {
+                debug!("root archive version: {}", version);
+                if self.archive_version != version {
+                    self.archive_version = version;
+                    self.roster_version += 1;
+                    true
+                } else {
+                    false
+                }
+            }
+            RootMsg::ImportShared => {
+                let question = t(
+                    "Replace my people, jobs, age groups, settings and chart with the shared ones?",
+                );
+                if !crate::web::confirm(question) {
+                    return false;
+                }
+
+                if let Some(shared) = self.shared.take() {
+                    debug!("importing shared chart");
+                    let mut local_store =
+                        StorageService::new(Area::Local).expect("failed to get storage");
+                    let week = shared.week();
+                    store_roster(shared.roster, &mut local_store);
+                    WeekStore::replace(&mut local_store, week);
+                }
+                share::leave_shared();
+                false
+            }
+            RootMsg::LeaveShared => {
+                share::leave_shared();
+                false
+            }
+            RootMsg::ChartChanged => {
+                self.roster_version += 1;
+                true
+            }
+            RootMsg::Undo | RootMsg::Redo if self.shared.is_some() => false,
+            RootMsg::Undo => {
+                let mut local_store =
+                    StorageService::new(Area::Local).expect("failed to get storage");
+                if history::undo(&mut local_store) {
+                    self.history_version += 1;
+                    self.roster_version += 1;
+                }
+                true
+            }
+            RootMsg::Redo => {
+                let mut local_store =
+                    StorageService::new(Area::Local).expect("failed to get storage");
+                if history::redo(&mut local_store) {
+                    self.history_version += 1;
+                    self.roster_version += 1;
+                }
+                true
+            }
+            RootMsg::Language(language) => {
+                if language != i18n::language() {
+                    let mut local_store =
+                        StorageService::new(Area::Local).expect("failed to get storage");
+                    language.store(&mut local_store);
+                    // everything is translated as it's drawn, so start over in the new language
+                    crate::web::reload();
+                }
+                false
+            }
+            RootMsg::KeyDown(event) => match shortcut(&event) {
+                Some(msg) => {
+                    event.prevent_default();
+                    self.update(msg)
+                }
+                None => false,
+            },
         }
     }
 
     fn view(&self) -> Html {
+        if let Some(ref shared) = self.shared {
+            return html! {
+                <div>
+                    <h1>{ t("Kitchen Patrol Charts") }</h1>
+                    <div class="shared">
+                        { t("A chart shared with you, it can't be changed here.") }{ " " }
+                        <button onclick=self.link.callback(|_| RootMsg::ImportShared)>{ t("Import into my chart") }</button>
+                        <button onclick=self.link.callback(|_| RootMsg::LeaveShared)>{ t("Open my chart") }</button>
+                    </div>
+                    <Chart shared=Some(shared.clone()) />
+                </div>
+            };
+        }
+
+        let share_error = match self.share_error {
+            Some(ref err) => {
+                html! { <div class="error">{ tf("Couldn't open the shared chart, {}", &[err]) }</div> }
+            }
+            None => html! {},
+        };
+
         html! {
             <div>
-                <h1>{"Kitchen Patrol Charts"}</h1>
-                <Chart people_version=self.people_version />
-                <PeopleModel on_save=self.link.callback(|inc| RootMsg::PeopleUpdated(inc)),/>
+                <h1>{ t("Kitchen Patrol Charts") }</h1>
+                { self.view_language() }
+                <WorkspaceModel />
+                { share_error }
+                { self.view_history() }
+                <Chart people_version=self.people_version jobs_version=self.jobs_version abilities_version=self.abilities_version history_version=self.history_version on_change=self.link.callback(|_| RootMsg::ChartChanged) on_archive=self.link.callback(RootMsg::ArchiveUpdated) />
+                <div class="editors">
+                    <PeopleModel abilities_version=self.abilities_version history_version=self.history_version on_save=self.link.callback(RootMsg::PeopleUpdated) />
+                    <JobsModel abilities_version=self.abilities_version history_version=self.history_version on_save=self.link.callback(RootMsg::JobsUpdated) />
+                    <AbilitiesModel history_version=self.history_version on_save=self.link.callback(RootMsg::AbilitiesUpdated) />
+                    <ArchiveModel archive_version=self.archive_version />
+                    <ExportModel roster_version=self.roster_version />
+                    <ServerModel />
+                </div>
             </div>
         }
     }
@@ -0,0 +1,56 @@
+use chrono::NaiveDate;
+use log::{debug, error};
+use serde::{Deserialize, Serialize};
+use yew::format::Json;
+use yew::services::StorageService;
+
+use crate::data::Settings;
+use crate::web::remote;
+use crate::web::workspace;
+
+pub(crate) const SETTINGS_KEY: &str = "settings_v1";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SettingsStore {
+    pub inc: usize,
+    pub settings: Settings,
+}
+
+impl SettingsStore {
+    pub fn restore(local_store: &mut StorageService) -> Option<Self> {
+        let from_store = local_store.restore(&workspace::key(SETTINGS_KEY));
+        match from_store {
+            Json(Ok(settings)) => Some(settings),
+            Json(Err(err)) => {
+                error!("could not load from local store: {}", err);
+                None
+            }
+        }
+    }
+
+    pub fn store(&mut self, local_store: &mut StorageService) {
+        self.inc += 1;
+        debug!("saving settings: {}", self.inc);
+        local_store.store(&workspace::key(SETTINGS_KEY), Json(self as &Self));
+        remote::push("settings");
+    }
+
+    /// The stored settings, or a chart starting today if none have been saved
+    pub fn restore_or_default(local_store: &mut StorageService) -> Self {
+        Self::restore(local_store).unwrap_or_else(|| Self {
+            inc: 0,
+            settings: Settings::new(today(), Settings::default().num_days()),
+        })
+    }
+}
+
+/// Today's date according to the browser
+pub fn today() -> NaiveDate {
+    let now = js_sys::Date::new_0();
+    NaiveDate::from_ymd_opt(
+        now.get_full_year() as i32,
+        now.get_month() + 1,
+        now.get_date(),
+    )
+    .unwrap_or_default()
+}
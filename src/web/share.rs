@@ -0,0 +1,33 @@
+use crate::data::{ShareError, SharedChart};
+
+/// Start of a link's fragment that holds a shared chart
+const SHARE_PREFIX: &str = "#chart=";
+
+/// The chart shared in this page's link, if it's a shared link
+pub fn shared_from_location() -> Option<Result<SharedChart, ShareError>> {
+    let hash = web_sys::window()?.location().hash().ok()?;
+    let encoded = hash.strip_prefix(SHARE_PREFIX)?;
+    Some(SharedChart::decode(encoded))
+}
+
+/// A link to this page that opens the chart read only
+pub fn share_link(shared: &SharedChart) -> Option<String> {
+    let location = web_sys::window()?.location();
+    let origin = location.origin().ok()?;
+    let path = location.pathname().ok()?;
+    Some(format!(
+        "{}{}{}{}",
+        origin,
+        path,
+        SHARE_PREFIX,
+        shared.encode()
+    ))
+}
+
+/// Drops the shared chart from the link and goes back to the chart in local storage
+pub fn leave_shared() {
+    if let Some(window) = web_sys::window() {
+        window.location().set_hash("").ok();
+    }
+    crate::web::reload();
+}
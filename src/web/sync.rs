@@ -0,0 +1,228 @@
+use std::future::Future;
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::spawn_local;
+use yew::callback::Callback;
+use yew::format::Json;
+use yew::services::{storage::Area, StorageService};
+
+use crate::data::{ChangeLog, PeopleOp};
+use crate::web::people::PeopleStore;
+use crate::web::remote::{fetch, put, request, ServerStore, Versioned};
+use crate::web::workspace;
+
+pub(crate) const PEOPLE_LOG_KEY: &str = "people_log_v1";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SyncStatus {
+    /// The server has every edit
+    Synced,
+    /// The server had newer people, which are now stored locally
+    Updated,
+    /// The server couldn't be reached, edits are kept until it can be
+    Offline,
+    /// The server's people changed since the waiting edits were made
+    Conflict,
+}
+
+/// People edits waiting to be sent to the server
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct PeopleLogStore {
+    pub log: ChangeLog<PeopleOp>,
+    /// Set when the server refused the edits, until someone picks which copy to keep
+    pub conflict: bool,
+}
+
+impl PeopleLogStore {
+    pub fn restore(local_store: &mut StorageService) -> Self {
+        match local_store.restore(&workspace::key(PEOPLE_LOG_KEY)) {
+            Json(Ok(log)) => log,
+            Json(Err(_)) => Self::default(),
+        }
+    }
+
+    pub fn store(&self, local_store: &mut StorageService) {
+        debug!("saving people log: {} edits", self.log.len());
+        local_store.store(&workspace::key(PEOPLE_LOG_KEY), Json(self));
+    }
+
+    /// Records saved edits for the server, nothing is recorded while there's no server
+    pub fn record<I: IntoIterator<Item = PeopleOp>>(local_store: &mut StorageService, ops: I) {
+        if ServerStore::restore(local_store).api_url("").is_none() {
+            return;
+        }
+
+        let mut stored = Self::restore(local_store);
+        stored.log.extend(ops);
+        stored.store(local_store);
+    }
+}
+
+fn json_error(err: serde_json::Error) -> JsValue {
+    err.to_string().into()
+}
+
+/// Stores the server's people locally, as a new local version so the editors load them
+fn store_people(local_store: &mut StorageService, people: PeopleStore) {
+    let mut local = PeopleStore {
+        inc: PeopleStore::restore(local_store).map_or(0, |p| p.inc),
+        people: people.people,
+    };
+    local.store(local_store);
+    PeopleLogStore {
+        log: ChangeLog::new(people.inc),
+        conflict: false,
+    }
+    .store(local_store);
+}
+
+async fn sync(url: String) -> Result<SyncStatus, JsValue> {
+    let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+    let stored = PeopleLogStore::restore(&mut local_store);
+    if stored.conflict {
+        return Ok(SyncStatus::Conflict);
+    }
+
+    if !stored.log.is_empty() {
+        let ops_url = format!("{}/ops", url);
+        let sent = stored.log.len();
+        let body = serde_json::to_string(&stored.log).map_err(json_error)?;
+
+        return match request("POST", &ops_url, Some(body)).await? {
+            (200, text) => {
+                let Versioned { inc } = serde_json::from_str(&text).map_err(json_error)?;
+                // more edits may have been saved while waiting
+                let mut stored = PeopleLogStore::restore(&mut local_store);
+                stored.log.accepted(sent, inc);
+                stored.store(&mut local_store);
+                Ok(SyncStatus::Synced)
+            }
+            (409, _) => {
+                warn!(
+                    "people changed on the server since version {}",
+                    stored.log.base
+                );
+                let mut stored = PeopleLogStore::restore(&mut local_store);
+                stored.conflict = true;
+                stored.store(&mut local_store);
+                Ok(SyncStatus::Conflict)
+            }
+            (status, text) => Err(format!("POST {} failed: {} {}", ops_url, status, text).into()),
+        };
+    }
+
+    // nothing waiting, so catch up with anyone else's edits
+    match fetch("GET", &url, None).await? {
+        Some(text) => {
+            let remote: PeopleStore = serde_json::from_str(&text).map_err(json_error)?;
+            if remote.inc == stored.log.base {
+                return Ok(SyncStatus::Synced);
+            }
+
+            debug!("people are newer on the server: {}", remote.inc);
+            store_people(&mut local_store, remote);
+            Ok(SyncStatus::Updated)
+        }
+        None => {
+            // the first browser to use the server shares its people
+            let people = PeopleStore {
+                inc: 0,
+                people: PeopleStore::restore(&mut local_store)
+                    .map(|p| p.people)
+                    .unwrap_or_else(crate::default_people),
+            };
+            let body = serde_json::to_string(&people).map_err(json_error)?;
+            match put(&url, 0, body).await? {
+                Some(inc) => {
+                    PeopleLogStore {
+                        log: ChangeLog::new(inc),
+                        conflict: false,
+                    }
+                    .store(&mut local_store);
+                    Ok(SyncStatus::Synced)
+                }
+                // another browser shared its people first, so take theirs like any later browser
+                None => match fetch("GET", &url, None).await? {
+                    Some(text) => {
+                        let remote = serde_json::from_str(&text).map_err(json_error)?;
+                        store_people(&mut local_store, remote);
+                        Ok(SyncStatus::Updated)
+                    }
+                    None => Ok(SyncStatus::Offline),
+                },
+            }
+        }
+    }
+}
+
+async fn resolve(url: String, keep_mine: bool) -> Result<SyncStatus, JsValue> {
+    let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+    let remote = match fetch("GET", &url, None).await? {
+        Some(text) => Some(serde_json::from_str::<PeopleStore>(&text).map_err(json_error)?),
+        None => None,
+    };
+
+    if keep_mine {
+        let base = remote.map_or(0, |r| r.inc);
+        let people = PeopleStore {
+            inc: base,
+            people: PeopleStore::restore(&mut local_store)
+                .map(|p| p.people)
+                .unwrap_or_default(),
+        };
+        let body = serde_json::to_string(&people).map_err(json_error)?;
+        match put(&url, base, body).await? {
+            Some(inc) => {
+                PeopleLogStore {
+                    log: ChangeLog::new(inc),
+                    conflict: false,
+                }
+                .store(&mut local_store);
+                Ok(SyncStatus::Synced)
+            }
+            // someone saved again while this was deciding, so it's still a conflict
+            None => Ok(SyncStatus::Conflict),
+        }
+    } else {
+        let remote = remote.unwrap_or(PeopleStore {
+            inc: 0,
+            people: Vec::new(),
+        });
+        store_people(&mut local_store, remote);
+        Ok(SyncStatus::Updated)
+    }
+}
+
+fn people_url() -> Option<String> {
+    let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+    ServerStore::restore(&mut local_store).api_url("people")
+}
+
+fn spawn_sync<F>(future: F, on_done: Callback<SyncStatus>)
+where
+    F: Future<Output = Result<SyncStatus, JsValue>> + 'static,
+{
+    spawn_local(async move {
+        let status = future.await.unwrap_or_else(|err| {
+            warn!("people kept locally, the server is unavailable: {:?}", err);
+            SyncStatus::Offline
+        });
+        on_done.emit(status);
+    });
+}
+
+/// Sends any waiting people edits to the server, or catches up with the server's
+pub fn sync_people(on_done: Callback<SyncStatus>) {
+    if let Some(url) = people_url() {
+        spawn_sync(sync(url), on_done);
+    }
+}
+
+/// Settles a conflict by overwriting the server's people with these, or these with the server's
+pub fn resolve_people(keep_mine: bool, on_done: Callback<SyncStatus>) {
+    if let Some(url) = people_url() {
+        spawn_sync(resolve(url, keep_mine), on_done);
+    }
+}
@@ -0,0 +1,334 @@
+use std::cell::Cell;
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+use yew::format::Json;
+use yew::prelude::*;
+use yew::services::{storage::Area, StorageService};
+
+use crate::web::abilities::ABILITIES_KEY;
+use crate::web::archive::ARCHIVE_KEY;
+use crate::web::chart::WEEK_KEY;
+use crate::web::export::{store_roster, stored_roster};
+use crate::web::history::HISTORY_KEY;
+use crate::web::i18n::{t, tf};
+use crate::web::jobs::JOBS_KEY;
+use crate::web::people::PEOPLE_KEY;
+use crate::web::remote::{SERVER_KEY, SYNCED_KEY};
+use crate::web::settings::SETTINGS_KEY;
+use crate::web::sync::PEOPLE_LOG_KEY;
+
+const WORKSPACES_KEY: &str = "workspaces_v1";
+
+/// Everything each chart keeps separately
+const WORKSPACE_KEYS: [&str; 10] = [
+    PEOPLE_KEY,
+    JOBS_KEY,
+    ABILITIES_KEY,
+    SETTINGS_KEY,
+    WEEK_KEY,
+    HISTORY_KEY,
+    PEOPLE_LOG_KEY,
+    SERVER_KEY,
+    SYNCED_KEY,
+    ARCHIVE_KEY,
+];
+
+/// A named chart, e.g. for one camp or trip, with its own people, jobs, settings and week
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Workspace {
+    pub id: usize,
+    pub name: String,
+}
+
+/// All the charts in this browser and which one is open
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WorkspacesStore {
+    pub current: usize,
+    pub workspaces: Vec<Workspace>,
+}
+
+impl Default for WorkspacesStore {
+    /// The chart that was here before there were more, it keeps using the original keys
+    fn default() -> Self {
+        Self {
+            current: 0,
+            workspaces: vec![Workspace {
+                id: 0,
+                name: t("My chart").to_string(),
+            }],
+        }
+    }
+}
+
+impl WorkspacesStore {
+    pub fn restore(local_store: &mut StorageService) -> Self {
+        match local_store.restore(WORKSPACES_KEY) {
+            Json(Ok(workspaces)) => workspaces,
+            Json(Err(_)) => Self::default(),
+        }
+    }
+
+    pub fn store(&self, local_store: &mut StorageService) {
+        debug!("saving workspaces, current: {}", self.current);
+        local_store.store(WORKSPACES_KEY, Json(self));
+    }
+
+    fn get(&self, id: usize) -> Option<&Workspace> {
+        self.workspaces.iter().find(|w| w.id == id)
+    }
+
+    /// Adds an empty chart, returning its id
+    fn add(&mut self, name: String) -> usize {
+        let id = self.workspaces.iter().map(|w| w.id + 1).max().unwrap_or(0);
+        self.workspaces.push(Workspace { id, name });
+        id
+    }
+}
+
+thread_local! {
+    static CURRENT: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// The open chart, read once from storage, switching charts reloads the page
+pub fn current() -> usize {
+    CURRENT.with(|current| match current.get() {
+        Some(id) => id,
+        None => {
+            let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+            let id = WorkspacesStore::restore(&mut local_store).current;
+            current.set(Some(id));
+            id
+        }
+    })
+}
+
+/// The storage key of a document in a chart
+fn key_in(id: usize, key: &str) -> String {
+    if id == 0 {
+        key.to_string()
+    } else {
+        format!("w{}_{}", id, key)
+    }
+}
+
+/// The storage key of a document in the open chart
+pub fn key(key: &str) -> String {
+    key_in(current(), key)
+}
+
+/// Runs `f` as though another chart was open, e.g. to read its roster
+fn in_workspace<T, F: FnOnce() -> T>(id: usize, f: F) -> T {
+    let open = current();
+    CURRENT.with(|current| current.set(Some(id)));
+    let result = f();
+    CURRENT.with(|current| current.set(Some(open)));
+    result
+}
+
+pub enum WorkspaceMsg {
+    Switch(usize),
+    NameInput(String),
+    Add,
+    Rename,
+    Delete,
+    CopyFromInput(usize),
+    Copy,
+}
+
+/// Picks which chart is open, adds and removes charts and copies rosters between them
+pub struct WorkspaceModel {
+    workspaces: WorkspacesStore,
+    name: String,
+    copy_from: Option<usize>,
+    link: ComponentLink<Self>,
+}
+
+impl WorkspaceModel {
+    fn store_and_reload(&self) {
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        self.workspaces.store(&mut local_store);
+        // every editor reads from storage when created, so start over with the other chart
+        crate::web::reload();
+    }
+}
+
+impl Component for WorkspaceModel {
+    type Message = WorkspaceMsg;
+    type Properties = ();
+
+    fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut local_store = StorageService::new(Area::Local).expect("failed to get storage");
+        let workspaces = WorkspacesStore::restore(&mut local_store);
+        let copy_from = workspaces
+            .workspaces
+            .iter()
+            .map(|w| w.id)
+            .find(|id| *id != workspaces.current);
+
+        Self {
+            workspaces,
+            name: String::new(),
+            copy_from,
+            link,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            WorkspaceMsg::Switch(id) => {
+                if id != self.workspaces.current && self.workspaces.get(id).is_some() {
+                    debug!("switching to workspace: {}", id);
+                    self.workspaces.current = id;
+                    self.store_and_reload();
+                }
+                false
+            }
+            WorkspaceMsg::NameInput(name) => {
+                self.name = name;
+                false
+            }
+            WorkspaceMsg::Add => {
+                let name = self.name.trim().to_string();
+                if name.is_empty() {
+                    return false;
+                }
+
+                let id = self.workspaces.add(name);
+                debug!("adding workspace: {}", id);
+                self.workspaces.current = id;
+                self.store_and_reload();
+                false
+            }
+            WorkspaceMsg::Rename => {
+                let name = self.name.trim().to_string();
+                let current = self.workspaces.current;
+                match self
+                    .workspaces
+                    .workspaces
+                    .iter_mut()
+                    .find(|w| w.id == current)
+                {
+                    Some(workspace) if !name.is_empty() => {
+                        workspace.name = name;
+                        self.name.clear();
+                        let mut local_store =
+                            StorageService::new(Area::Local).expect("failed to get storage");
+                        self.workspaces.store(&mut local_store);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            WorkspaceMsg::Delete => {
+                // there's always a chart open
+                if self.workspaces.workspaces.len() < 2 {
+                    return false;
+                }
+
+                let current = self.workspaces.current;
+                let name = self.workspaces.get(current).map(|w| w.name.clone());
+                let question = tf(
+                    "Delete the {} chart, with its people, jobs, past charts and history?",
+                    &[&name.unwrap_or_default()],
+                );
+                if !crate::web::confirm(&question) {
+                    return false;
+                }
+
+                debug!("deleting workspace: {}", current);
+                let mut local_store =
+                    StorageService::new(Area::Local).expect("failed to get storage");
+                for key in WORKSPACE_KEYS.iter() {
+                    local_store.remove(&key_in(current, key));
+                }
+                self.workspaces.workspaces.retain(|w| w.id != current);
+                self.workspaces.current = self.workspaces.workspaces[0].id;
+                self.store_and_reload();
+                false
+            }
+            WorkspaceMsg::CopyFromInput(id) => {
+                self.copy_from = Some(id);
+                false
+            }
+            WorkspaceMsg::Copy => {
+                let from = match self.copy_from.filter(|id| *id != self.workspaces.current) {
+                    Some(from) => from,
+                    None => return false,
+                };
+
+                let name = self.workspaces.get(from).map(|w| w.name.clone());
+                let question = tf(
+                    "Replace the people, jobs, age groups and settings here with the ones from {}?",
+                    &[&name.unwrap_or_default()],
+                );
+                if !crate::web::confirm(&question) {
+                    return false;
+                }
+
+                debug!("copying the roster from workspace: {}", from);
+                let mut local_store =
+                    StorageService::new(Area::Local).expect("failed to get storage");
+                let roster = in_workspace(from, || stored_roster(&mut local_store));
+                store_roster(roster, &mut local_store);
+                crate::web::reload();
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, _props: Self::Properties) -> ShouldRender {
+        false
+    }
+
+    fn view(&self) -> Html {
+        let current = self.workspaces.current;
+        let others = self
+            .workspaces
+            .workspaces
+            .iter()
+            .filter(|w| w.id != current)
+            .collect::<Vec<_>>();
+        let option = |selected: Option<usize>| {
+            move |workspace: &Workspace| {
+                html! {
+                    <option value={workspace.id.to_string()} selected=selected == Some(workspace.id)>{ &workspace.name }</option>
+                }
+            }
+        };
+        let value = |e: ChangeData| match e {
+            ChangeData::Select(se) => se.value().parse().unwrap_or_default(),
+            _ => unreachable!(),
+        };
+
+        let copy = if others.is_empty() {
+            html! {}
+        } else {
+            html! {
+                <span>
+                    <select aria-label=t("Chart to copy from") onchange=self.link.callback(move |e| WorkspaceMsg::CopyFromInput(value(e)))>
+                        { for others.iter().copied().map(option(self.copy_from)) }
+                    </select>
+                    <button type="button" onclick=self.link.callback(|_| WorkspaceMsg::Copy)>{ t("Copy its roster here") }</button>
+                    <button type="button" onclick=self.link.callback(|_| WorkspaceMsg::Delete)>
+                        { tf("Delete {}", &[&self.workspaces.get(current).map(|w| w.name.as_str()).unwrap_or_default()]) }
+                    </button>
+                </span>
+            }
+        };
+
+        html! {
+            <div class="workspaces">
+                <label>{ t("Chart") }{" "}
+                    <select onchange=self.link.callback(move |e| WorkspaceMsg::Switch(value(e)))>
+                        { for self.workspaces.workspaces.iter().map(option(Some(current))) }
+                    </select>
+                </label>
+                <input type="text" aria-label=t("Chart name") placeholder=t("Thanksgiving") value={&self.name} oninput=self.link.callback(|e: InputData| WorkspaceMsg::NameInput(e.value)) />
+                <button type="button" onclick=self.link.callback(|_| WorkspaceMsg::Add)>{ t("New chart") }</button>
+                <button type="button" onclick=self.link.callback(|_| WorkspaceMsg::Rename)>{ t("Rename") }</button>
+                { copy }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use chrono::Duration;
+use kp_chart::data::*;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// The most days the chart can be set to, same as the web app
+const MAX_DAYS: usize = 31;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Tab {
+    Chart,
+    People,
+    Jobs,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 3] = [Tab::Chart, Tab::People, Tab::Jobs];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Tab::Chart => "Chart",
+            Tab::People => "People",
+            Tab::Jobs => "Jobs",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|t| *t == self)
+            .unwrap_or_default()
+    }
+
+    fn next(self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    fn prev(self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+/// The field being typed into
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Field {
+    PersonName,
+    PersonTags,
+    JobName,
+    JobNeeds,
+    JobWhen,
+}
+
+impl Field {
+    pub fn label(self) -> &'static str {
+        match self {
+            Field::PersonName => "Name",
+            Field::PersonTags => "Skills, comma separated",
+            Field::JobName => "Job",
+            Field::JobNeeds => "Needs, e.g. Adult +driver; Teen",
+            Field::JobWhen => "When, e.g. daily, every 2 days, Mon, Wed or 2020-07-04",
+        }
+    }
+}
+
+pub struct Input {
+    pub field: Field,
+    pub text: String,
+}
+
+pub struct App {
+    roster: Roster,
+    week: Week,
+    tab: Tab,
+    person: usize,
+    job: usize,
+    input: Option<Input>,
+    status: Option<String>,
+    changed: bool,
+    done: bool,
+}
+
+/// Needs in the form `Slot::from_str` reads back
+fn needs_to_string(job: &Job) -> String {
+    job.people()
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl App {
+    pub fn new(roster: Roster) -> Self {
+        let week = roster.calculate();
+
+        Self {
+            roster,
+            week,
+            tab: Tab::Chart,
+            person: 0,
+            job: 0,
+            input: None,
+            status: None,
+            changed: false,
+            done: false,
+        }
+    }
+
+    pub fn roster(&self) -> &Roster {
+        &self.roster
+    }
+
+    pub fn week(&self) -> &Week {
+        &self.week
+    }
+
+    pub fn tab(&self) -> Tab {
+        self.tab
+    }
+
+    pub fn person(&self) -> usize {
+        self.person
+    }
+
+    pub fn job(&self) -> usize {
+        self.job
+    }
+
+    pub fn input(&self) -> Option<&Input> {
+        self.input.as_ref()
+    }
+
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    pub fn set_status(&mut self, status: String) {
+        self.status = Some(status);
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// True once after each edit to the roster, i.e. when it should be saved
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::replace(&mut self.changed, false)
+    }
+
+    pub fn on_key(&mut self, key: KeyEvent) {
+        if self.input.is_some() {
+            self.on_input_key(key);
+            return;
+        }
+
+        self.status = None;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.done = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.done = true,
+            KeyCode::Tab | KeyCode::Right => self.tab = self.tab.next(),
+            KeyCode::BackTab | KeyCode::Left => self.tab = self.tab.prev(),
+            KeyCode::Char('1') => self.tab = Tab::Chart,
+            KeyCode::Char('2') => self.tab = Tab::People,
+            KeyCode::Char('3') => self.tab = Tab::Jobs,
+            KeyCode::Up | KeyCode::Char('k') => self.select(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.select(1),
+            KeyCode::Char(c) => match self.tab {
+                Tab::Chart => self.on_chart_key(c),
+                Tab::People => self.on_people_key(c),
+                Tab::Jobs => self.on_jobs_key(c),
+            },
+            _ => (),
+        }
+    }
+
+    fn on_input_key(&mut self, key: KeyEvent) {
+        let input = match self.input.as_mut() {
+            Some(input) => input,
+            None => return,
+        };
+
+        match key.code {
+            KeyCode::Esc => self.input = None,
+            KeyCode::Enter => {
+                if let Some(input) = self.input.take() {
+                    self.commit(input);
+                }
+            }
+            KeyCode::Backspace => {
+                input.text.pop();
+            }
+            KeyCode::Char(c) => input.text.push(c),
+            _ => (),
+        }
+    }
+
+    fn on_chart_key(&mut self, c: char) {
+        let settings = &mut self.roster.settings;
+        match c {
+            '[' if settings.num_days() > 1 => settings.set_num_days(settings.num_days() - 1),
+            ']' if settings.num_days() < MAX_DAYS => settings.set_num_days(settings.num_days() + 1),
+            '<' => settings.set_start(settings.start() - Duration::days(1)),
+            '>' => settings.set_start(settings.start() + Duration::days(1)),
+            _ => return,
+        }
+        self.recalculate();
+    }
+
+    fn on_people_key(&mut self, c: char) {
+        match c {
+            'a' => {
+                let ability = self.roster.abilities.first().cloned().unwrap_or_default();
+                self.roster.people.push(Person::new("Jane Doe", ability));
+                self.person = self.roster.people.len() - 1;
+                self.recalculate();
+                self.edit(Field::PersonName);
+            }
+            'd' if self.person < self.roster.people.len() => {
+                let person = self.roster.people.remove(self.person);
+                self.status = Some(format!("deleted {}", person));
+                self.select(0);
+                self.recalculate();
+            }
+            'b' => self.cycle_ability(),
+            'n' => self.edit(Field::PersonName),
+            't' => self.edit(Field::PersonTags),
+            _ => (),
+        }
+    }
+
+    fn on_jobs_key(&mut self, c: char) {
+        match c {
+            'a' => {
+                let ability = self.roster.abilities.first().cloned().unwrap_or_default();
+                self.roster.jobs.push(Job::new("New job", vec![ability]));
+                self.job = self.roster.jobs.len() - 1;
+                self.recalculate();
+                self.edit(Field::JobName);
+            }
+            'd' if self.job < self.roster.jobs.len() => {
+                let job = self.roster.jobs.remove(self.job);
+                self.status = Some(format!("deleted {}", job.name()));
+                self.select(0);
+                self.recalculate();
+            }
+            'n' => self.edit(Field::JobName),
+            'e' => self.edit(Field::JobNeeds),
+            'w' => self.edit(Field::JobWhen),
+            _ => (),
+        }
+    }
+
+    /// Moves the selection in the current list, keeping it in bounds
+    fn select(&mut self, delta: isize) {
+        let (selected, len) = match self.tab {
+            Tab::Chart => return,
+            Tab::People => (&mut self.person, self.roster.people.len()),
+            Tab::Jobs => (&mut self.job, self.roster.jobs.len()),
+        };
+
+        *selected = selected
+            .saturating_add_signed(delta)
+            .min(len.saturating_sub(1));
+    }
+
+    /// Starts editing a field of the selected person or job, prefilled with its current value
+    fn edit(&mut self, field: Field) {
+        let person = self.roster.people.get(self.person);
+        let job = self.roster.jobs.get(self.job);
+        let text = match field {
+            Field::PersonName => person.map(|p| p.name().to_string()),
+            Field::PersonTags => person.map(|p| tags_to_string(p.tags())),
+            Field::JobName => job.map(|j| j.name().to_string()),
+            Field::JobNeeds => job.map(needs_to_string),
+            Field::JobWhen => job.map(|j| j.recurrence().to_string()),
+        };
+
+        if let Some(text) = text {
+            self.input = Some(Input { field, text });
+        }
+    }
+
+    fn commit(&mut self, input: Input) {
+        let text = input.text.trim();
+        let person = self.roster.people.get_mut(self.person);
+        let job = self.roster.jobs.get_mut(self.job);
+
+        match (input.field, person, job) {
+            (Field::PersonName, Some(person), _) => person.set_name(text.to_string()),
+            (Field::PersonTags, Some(person), _) => person.set_tags(parse_tags(text)),
+            (Field::JobName, _, Some(job)) => job.set_name(text.to_string()),
+            (Field::JobNeeds, _, Some(job)) => {
+                let slots = text
+                    .split(';')
+                    .filter(|s| !s.trim().is_empty())
+                    .map(str::parse::<Slot>)
+                    .collect::<Result<Vec<_>, _>>();
+                match slots {
+                    Ok(slots) => *job.people_mut() = slots,
+                    Err(err) => {
+                        self.status = Some(err);
+                        return;
+                    }
+                }
+            }
+            (Field::JobWhen, _, Some(job)) => match text.parse::<Recurrence>() {
+                Ok(recurrence) => job.set_recurrence(recurrence),
+                Err(err) => {
+                    self.status = Some(err);
+                    return;
+                }
+            },
+            _ => return,
+        }
+        self.recalculate();
+    }
+
+    fn cycle_ability(&mut self) {
+        let abilities = &self.roster.abilities;
+        let person = match self.roster.people.get_mut(self.person) {
+            Some(person) => person,
+            None => return,
+        };
+
+        let next = abilities
+            .iter()
+            .position(|a| a == person.ability())
+            .and_then(|i| abilities.get(i + 1))
+            .or_else(|| abilities.first());
+        if let Some(ability) = next {
+            person.set_ability(ability.clone());
+            self.recalculate();
+        }
+    }
+
+    /// Calculates the chart again, it's only saved once the people and jobs are all valid
+    fn recalculate(&mut self) {
+        self.week = self.roster.calculate();
+        match self.problem() {
+            Some(problem) => self.status = Some(format!("not saved, {}", problem)),
+            None => self.changed = true,
+        }
+    }
+
+    /// The first thing that has to be fixed before the roster can be saved
+    fn problem(&self) -> Option<String> {
+        let roster = &self.roster;
+        let people = validate_people(&roster.people, &roster.abilities);
+        for (person, errors) in roster.people.iter().zip(people) {
+            if let Some(err) = errors.name.or(errors.ability) {
+                return Some(format!("person \"{}\": {}", person.name(), err));
+            }
+        }
+        for (job, errors) in roster.jobs.iter().zip(validate_jobs(&roster.jobs)) {
+            if let Some(err) = errors.name.or(errors.slots).or(errors.recurrence) {
+                return Some(format!("job \"{}\": {}", job.name(), err));
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,88 @@
+//! Terminal Kitchen Patrol chart editor
+//!
+//! Lists and edits the people and jobs and shows the calculated chart. Everything is saved to a
+//!  JSON file in the same format as the web app's export, so the two can be moved between.
+
+mod app;
+mod ui;
+
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use chrono::Local;
+use clap::Parser;
+use kp_chart::data::{Roster, Settings};
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+
+use crate::app::App;
+
+#[derive(Parser)]
+#[command(
+    name = "kp-chart-tui",
+    version,
+    about = "Edits a Kitchen Patrol chart in the terminal"
+)]
+struct Args {
+    /// The chart file, an export from the web app, created if it doesn't exist
+    #[arg(default_value = "kp-chart.json")]
+    file: PathBuf,
+}
+
+/// Reads the roster, or starts from the example family if the file doesn't exist yet
+fn load(path: &Path) -> Result<Roster, Box<dyn Error>> {
+    match fs::read(path) {
+        Ok(data) => Ok(serde_json::from_slice(&data)?),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Roster {
+            people: kp_chart::default_people(),
+            jobs: kp_chart::default_jobs(),
+            settings: Settings::new(Local::now().date_naive(), Settings::default().num_days()),
+            ..Roster::default()
+        }),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn save(path: &Path, roster: &Roster) -> Result<(), Box<dyn Error>> {
+    fs::write(path, serde_json::to_string_pretty(roster)?)?;
+    Ok(())
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let mut app = App::new(load(&args.file)?);
+
+    let mut terminal = ratatui::init();
+    let result = loop {
+        if let Err(err) = terminal.draw(|frame| ui::draw(frame, &app)) {
+            break Err(err.into());
+        }
+
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => app.on_key(key),
+            Ok(_) => continue,
+            Err(err) => break Err(err.into()),
+        }
+
+        if app.take_changed() {
+            if let Err(err) = save(&args.file, app.roster()) {
+                app.set_status(format!("failed to save {}: {}", args.file.display(), err));
+            }
+        }
+
+        if app.is_done() {
+            break Ok(());
+        }
+    };
+    ratatui::restore();
+
+    result
+}
+
+fn main() {
+    if let Err(err) = run(Args::parse()) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,146 @@
+use kp_chart::data::tags_to_string;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Row, Table, TableState, Tabs};
+use ratatui::Frame;
+
+use crate::app::{App, Tab};
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [tabs, body, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(0),
+        Constraint::Length(3),
+    ])
+    .areas(frame.area());
+
+    let titles = Tab::ALL.iter().map(|t| t.title());
+    let selected = Tab::ALL.iter().position(|t| *t == app.tab());
+    frame.render_widget(
+        Tabs::new(titles)
+            .select(selected)
+            .highlight_style(Style::new().bold().reversed())
+            .block(Block::bordered().title(" Kitchen Patrol Charts ")),
+        tabs,
+    );
+
+    match app.tab() {
+        Tab::Chart => draw_chart(frame, app, body),
+        Tab::People => draw_people(frame, app, body),
+        Tab::Jobs => draw_jobs(frame, app, body),
+    }
+
+    draw_footer(frame, app, footer);
+}
+
+fn draw_chart(frame: &mut Frame, app: &App, area: Rect) {
+    let mut rows = app.week().to_rows().into_iter();
+    let header = rows.next().unwrap_or_default();
+    let job_width = app
+        .week()
+        .jobs()
+        .map(|j| j.name().len())
+        .chain(Some(header.first().map_or(0, String::len)))
+        .max()
+        .unwrap_or_default();
+
+    let widths = Some(Constraint::Length(job_width as u16))
+        .into_iter()
+        .chain(header.iter().skip(1).map(|_| Constraint::Fill(1)));
+    let settings = &app.roster().settings;
+    let title = format!(
+        " {} days from {} ",
+        settings.num_days(),
+        settings.start().format("%a %-m/%-d/%Y")
+    );
+
+    frame.render_widget(
+        Table::new(rows.map(Row::new), widths)
+            .header(Row::new(header).add_modifier(Modifier::BOLD))
+            .block(Block::bordered().title(title)),
+        area,
+    );
+}
+
+fn draw_people(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = app.roster().people.iter().map(|p| {
+        Row::new(vec![
+            p.name().to_string(),
+            p.ability().to_string(),
+            tags_to_string(p.tags()),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(2),
+            Constraint::Fill(1),
+            Constraint::Fill(2),
+        ],
+    )
+    .header(Row::new(vec!["Name", "Group", "Skills"]).add_modifier(Modifier::BOLD))
+    .row_highlight_style(Style::new().reversed())
+    .block(Block::bordered().title(" All the beautiful people "));
+
+    let mut state = TableState::new().with_selected(Some(app.person()));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_jobs(frame: &mut Frame, app: &App, area: Rect) {
+    let rows = app.roster().jobs.iter().map(|j| {
+        let needs = j
+            .people()
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        Row::new(vec![
+            j.name().to_string(),
+            needs,
+            j.recurrence().to_string(),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(2),
+            Constraint::Fill(2),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(Row::new(vec!["Job", "Needs", "When"]).add_modifier(Modifier::BOLD))
+    .row_highlight_style(Style::new().reversed())
+    .block(Block::bordered().title(" Jobs "));
+
+    let mut state = TableState::new().with_selected(Some(app.job()));
+    frame.render_stateful_widget(table, area, &mut state);
+}
+
+fn draw_footer(frame: &mut Frame, app: &App, area: Rect) {
+    if let Some(input) = app.input() {
+        frame.set_cursor_position((area.x + 1 + input.text.len() as u16, area.y + 1));
+        frame.render_widget(
+            Paragraph::new(input.text.as_str()).block(
+                Block::bordered()
+                    .title(format!(" {} ", input.field.label()))
+                    .title_bottom(" enter to save, esc to cancel "),
+            ),
+            area,
+        );
+        return;
+    }
+
+    let help = match app.tab() {
+        Tab::Chart => "[ ] days, < > start date",
+        Tab::People => "a add, d delete, n name, b group, t skills",
+        Tab::Jobs => "a add, d delete, n name, e needs, w when",
+    };
+    let line = match app.status() {
+        Some(status) => Line::from(status).red(),
+        None => Line::from(format!("{}, tab switch, q quit", help)),
+    };
+    frame.render_widget(Paragraph::new(line).block(Block::bordered()), area);
+}
@@ -20,6 +20,7 @@ module.exports = {
         ),
         new WasmPackPlugin({
             crateDirectory: __dirname, // Define where the root of the rust code is located (where the cargo.toml file is located)
+            extraArgs: "-- --features web", // the browser app is behind the web feature
         }),
     ]
 };
\ No newline at end of file